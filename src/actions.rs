@@ -0,0 +1,234 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use piston::input::Button;
+use piston::input::ControllerButton;
+use piston::input::Key;
+use piston::input::MouseButton;
+use ron::ser::PrettyConfig;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::gamepad;
+use crate::gamepad::controller_button;
+
+
+/// what the player means by an input, independent of which button they used. handlers match on
+/// these instead of raw buttons so that every control can be rebound
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Action {
+    /// primary pointer button, activates whatever is under the cursor
+    Select,
+    Confirm,
+    Back,
+    Up,
+    Down,
+    /// adjust the focused widget, e.g. move a slider
+    Left,
+    Right,
+    Next,
+    /// delete the last character typed
+    Erase,
+    Pause,
+    TimeWarpUp,
+    TimeWarpDown,
+    /// show or hide the debug overlay
+    ToggleOverlay,
+    /// open or close the developer console
+    ToggleConsole,
+    /// save the current frame as a PNG
+    Screenshot,
+}
+
+/// where an action is used. a button may be bound to several actions as long as no two of them can
+/// be active at the same time
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Scope {
+    Global,
+    Menu,
+    Mission,
+}
+
+impl Action {
+    pub fn scope(self) -> Scope {
+        match self {
+            Action::Select | Action::ToggleOverlay | Action::ToggleConsole | Action::Screenshot => Scope::Global,
+            Action::Confirm | Action::Back | Action::Up | Action::Down | Action::Left | Action::Right | Action::Next
+                | Action::Erase => Scope::Menu,
+            Action::Pause | Action::TimeWarpUp | Action::TimeWarpDown => Scope::Mission,
+        }
+    }
+
+    /// whether two actions could both be triggered by the same input at once
+    pub fn overlaps(self, other: Action) -> bool {
+        let (a, b) = (self.scope(), other.scope());
+        a == b || a == Scope::Global || b == Scope::Global
+    }
+}
+
+
+/// the same button bound to two actions which overlap
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Conflict {
+    pub button: Button,
+    pub actions: (Action, Action),
+}
+
+
+/// which buttons trigger each action
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Bindings {
+    bindings: BTreeMap<Action, Vec<Button>>,
+}
+
+impl Bindings {
+    pub fn defaults() -> Bindings {
+        let mut bindings = BTreeMap::new();
+        bindings.insert(Action::Select, vec![Button::Mouse(MouseButton::Left)]);
+        bindings.insert(Action::Confirm, vec![Button::Keyboard(Key::Return), controller_button(gamepad::BUTTON_A)]);
+        bindings.insert(Action::Back, vec![Button::Keyboard(Key::Escape), controller_button(gamepad::BUTTON_B)]);
+        bindings.insert(Action::Up, vec![Button::Keyboard(Key::Up), controller_button(gamepad::BUTTON_DPAD_UP)]);
+        bindings.insert(Action::Down, vec![Button::Keyboard(Key::Down), controller_button(gamepad::BUTTON_DPAD_DOWN)]);
+        bindings.insert(Action::Left, vec![Button::Keyboard(Key::Left), controller_button(gamepad::BUTTON_DPAD_LEFT)]);
+        bindings.insert(Action::Right, vec![Button::Keyboard(Key::Right), controller_button(gamepad::BUTTON_DPAD_RIGHT)]);
+        bindings.insert(Action::Next, vec![Button::Keyboard(Key::Tab)]);
+        bindings.insert(Action::Erase, vec![Button::Keyboard(Key::Backspace)]);
+        bindings.insert(Action::Pause, vec![Button::Keyboard(Key::Escape), controller_button(gamepad::BUTTON_START)]);
+        bindings.insert(Action::TimeWarpUp, vec![Button::Keyboard(Key::Period), controller_button(gamepad::BUTTON_RIGHT_SHOULDER)]);
+        bindings.insert(Action::TimeWarpDown, vec![Button::Keyboard(Key::Comma), controller_button(gamepad::BUTTON_LEFT_SHOULDER)]);
+        bindings.insert(Action::ToggleOverlay, vec![Button::Keyboard(Key::F3)]);
+        bindings.insert(Action::ToggleConsole, vec![Button::Keyboard(Key::Backquote)]);
+        bindings.insert(Action::Screenshot, vec![Button::Keyboard(Key::F12)]);
+        Bindings { bindings }
+    }
+
+    /// the user's keybinding file in the platform config directory
+    pub fn user_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("mars").join("keybindings.ron"))
+    }
+
+    /// the defaults overridden by whichever actions the file at path binds. the file is a RON map
+    /// from action to buttons, e.g. `{ Pause: [Keyboard(P)], Confirm: [Keyboard(Return), Keyboard(Space)] }`
+    pub fn load(path: &Path) -> Result<Bindings, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        let overrides: BTreeMap<Action, Vec<Button>> = ron::de::from_str(&text)
+            .map_err(|e| format!("failed to parse {}: {}", path.display(), e))?;
+        let mut bindings = Bindings::defaults();
+        bindings.bindings.extend(overrides);
+        match bindings.conflicts().first() {
+            Some(Conflict { button, actions: (a, b) }) => {
+                Err(format!("{:?} is bound to both {:?} and {:?} in {}", button, a, b, path.display()))
+            },
+            None => Ok(bindings),
+        }
+    }
+
+    /// every action the button is bound to. bindings apply to every controller, so the controller
+    /// a button came from doesn't matter
+    pub fn actions(&self, button: Button) -> Vec<Action> {
+        let button = match button {
+            Button::Controller(ControllerButton { button, .. }) => controller_button(button),
+            _ => button,
+        };
+        self.bindings.iter()
+            .filter(|(_, buttons)| buttons.contains(&button))
+            .map(|(action, _)| *action)
+            .collect()
+    }
+
+    /// the buttons bound to action
+    pub fn buttons(&self, action: Action) -> &[Button] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// bind button to action in place of the action's other buttons of the same kind, so binding a
+    /// key keeps the controller binding and the other way around. refused if the button is already
+    /// bound to an overlapping action
+    pub fn rebind(&mut self, action: Action, button: Button) -> Result<(), String> {
+        let button = match button {
+            Button::Controller(ControllerButton { button, .. }) => controller_button(button),
+            _ => button,
+        };
+        let controller = |button: &Button| matches!(button, Button::Controller(_));
+        let mut rebound = self.clone();
+        let buttons = rebound.bindings.entry(action).or_default();
+        buttons.retain(|bound| controller(bound) != controller(&button));
+        buttons.push(button);
+        if let Some(Conflict { button, actions: (a, b) }) = rebound.conflicts().first() {
+            let other = if *a == action { b } else { a };
+            return Err(format!("{:?} is already bound to {:?}", button, other))
+        }
+        *self = rebound;
+        Ok(())
+    }
+
+    /// write every binding to path, in the format load reads
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+        }
+        let text = ron::ser::to_string_pretty(&self.bindings, PrettyConfig::default()).map_err(|e| e.to_string())?;
+        fs::write(path, text).map_err(|e| format!("failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut conflicts = Vec::new();
+        for (i, (a, a_buttons)) in self.bindings.iter().enumerate() {
+            for (b, b_buttons) in self.bindings.iter().skip(i + 1) {
+                if !a.overlaps(*b) {
+                    continue
+                }
+                for button in a_buttons.iter().filter(|button| b_buttons.contains(button)) {
+                    conflicts.push(Conflict { button: *button, actions: (*a, *b) });
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_have_no_conflicts() {
+        assert_eq!(Bindings::defaults().conflicts(), Vec::new());
+    }
+
+    #[test]
+    fn rebinding_replaces_buttons_of_the_same_kind() {
+        let mut bindings = Bindings::defaults();
+        bindings.rebind(Action::Pause, Button::Keyboard(Key::P)).unwrap();
+        assert_eq!(bindings.buttons(Action::Pause), [controller_button(gamepad::BUTTON_START), Button::Keyboard(Key::P)]);
+
+        // bindings apply to every controller, so the one it was rebound from doesn't matter
+        let from_second_controller = Button::Controller(ControllerButton::new(3, gamepad::BUTTON_Y));
+        bindings.rebind(Action::Pause, from_second_controller).unwrap();
+        assert_eq!(bindings.buttons(Action::Pause), [Button::Keyboard(Key::P), controller_button(gamepad::BUTTON_Y)]);
+        assert_eq!(bindings.actions(Button::Controller(ControllerButton::new(1, gamepad::BUTTON_Y))), [Action::Pause]);
+    }
+
+    #[test]
+    fn rebinding_to_an_overlapping_action_is_refused() {
+        let mut bindings = Bindings::defaults();
+        assert_eq!(bindings.rebind(Action::Up, Button::Keyboard(Key::Down)), Err("Keyboard(Down) is already bound to Down".to_string()));
+        assert_eq!(bindings.buttons(Action::Up), [Button::Keyboard(Key::Up), controller_button(gamepad::BUTTON_DPAD_UP)]);
+
+        // the menu and the mission never take input at the same time
+        bindings.rebind(Action::TimeWarpUp, Button::Keyboard(Key::Return)).unwrap();
+        assert_eq!(bindings.actions(Button::Keyboard(Key::Return)), [Action::Confirm, Action::TimeWarpUp]);
+    }
+
+    #[test]
+    fn conflicts_name_both_actions() {
+        let mut bindings = Bindings::defaults();
+        bindings.bindings.insert(Action::Pause, vec![Button::Keyboard(Key::F3)]);
+        let conflict = Conflict { button: Button::Keyboard(Key::F3), actions: (Action::Pause, Action::ToggleOverlay) };
+        assert_eq!(bindings.conflicts(), [conflict]);
+    }
+}
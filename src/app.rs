@@ -1,20 +1,66 @@
+use std::path::PathBuf;
+
 use graphics::Context;
-use glutin_window::GlutinWindow;
-use opengl_graphics::GlGraphics;
 use piston::input::Button;
-use piston::input::keyboard::Key;
-use piston::window::Window;
-use piston::window::Size as WindowSize;
+use piston::input::ControllerAxisArgs;
+use piston::input::Key;
+use rand::Rng;
+use serde::Deserialize;
+use serde::Serialize;
 
+use crate::actions::Action;
+use crate::actions::Bindings;
+use crate::axis::Axis;
+use crate::colors;
+use crate::config::Config;
+use crate::config::Setting;
+use crate::console;
+use crate::console::Command;
+use crate::console::Console;
+use crate::console::Destination;
+use crate::events::EventBus;
+use crate::events::Subscribe;
+use crate::gamepad;
 use crate::hover::Hover;
+use crate::layout;
+use crate::load::LoadState;
 use crate::menu::MenuState;
+use crate::mission::MissionPhase;
 use crate::mission::MissionState;
+use crate::options::OptionsState;
+use crate::overlay::DebugOverlay;
 use crate::press::Press;
 use crate::release::Release;
+use crate::render::Backend;
 use crate::render::Render;
+use crate::replay::Input;
+use crate::replay::Recorder;
+use crate::replay::Replay;
+use crate::resize::Resize;
+use crate::rng;
+use crate::rng::GameRng;
+use crate::saves;
+use crate::scene::Scene;
+use crate::scene::SceneStack;
+use crate::scroll::Scroll;
+use crate::sounds::Sound;
+use crate::text::Text;
+use crate::update::FixedTimestep;
+use crate::update::TIMESTEP;
+use crate::update::Update;
+
+
+/// how far the left stick has to be pushed before it counts as a d-pad press
+const STICK_THRESHOLD: f64 = 0.5;
 
+/// the range time warp is kept within, however it's set
+const MIN_TIME_WARP: f64 = 0.125;
+const MAX_TIME_WARP: f64 = 64.0;
+/// how much Action::TimeWarpUp multiplies the time warp by, and Action::TimeWarpDown divides it by
+const TIME_WARP_STEP: f64 = 2.0;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum MissionDifficulty {
     Easy,
     Medium,
@@ -22,95 +68,554 @@ pub enum MissionDifficulty {
 }
 
 
-/// returned from various event handlers to allow lower level components to affect app level state
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+/// emitted from various event handlers to allow lower level components to affect app level state
+/// and to let subsystems react to each other
+#[derive(Clone, Debug, PartialEq)]
 pub enum AppEvent {
     Exit,
     OpenMenu,
     ResumeMission,
-    NewMission(MissionDifficulty),
+    /// start a mission, from the given seed or one drawn from the session's rng
+    NewMission(MissionDifficulty, Option<u64>),
+    /// save the running mission to the slot it was loaded from or last saved to, or a new one
+    SaveMission,
+    OpenLoadScreen,
+    CloseLoadScreen,
+    /// replace any running mission with the one saved in this file
+    LoadMission(PathBuf),
+    /// replace any running mission with the one in this save file text, from the slot named. how
+    /// --load launches, so replays don't need the file
+    RestoreMission(Option<String>, String),
+    /// a save slot was renamed from the first name to the second
+    RenamedSave(String, String),
+    PlaySound(Sound),
+    OpenOptions,
+    CloseOptions,
+    /// apply a setting now. it's saved to the config file with the next SaveSettings
+    ChangeSetting(Setting),
+    /// write the settings changed since they were last saved to the config file
+    SaveSettings,
+    /// bind the next button pressed to the action, unless it's bound to Back
+    Rebind(Action),
+    /// speed time up a step, or slow it down a step for false
+    StepTimeWarp(bool),
 }
 
+impl AppEvent {
+    /// the kind of event, without its data
+    pub fn name(&self) -> &'static str {
+        match self {
+            AppEvent::Exit => "Exit",
+            AppEvent::OpenMenu => "OpenMenu",
+            AppEvent::ResumeMission => "ResumeMission",
+            AppEvent::NewMission(..) => "NewMission",
+            AppEvent::SaveMission => "SaveMission",
+            AppEvent::OpenLoadScreen => "OpenLoadScreen",
+            AppEvent::CloseLoadScreen => "CloseLoadScreen",
+            AppEvent::LoadMission(_) => "LoadMission",
+            AppEvent::RestoreMission(..) => "RestoreMission",
+            AppEvent::RenamedSave(..) => "RenamedSave",
+            AppEvent::PlaySound(_) => "PlaySound",
+            AppEvent::OpenOptions => "OpenOptions",
+            AppEvent::CloseOptions => "CloseOptions",
+            AppEvent::ChangeSetting(_) => "ChangeSetting",
+            AppEvent::SaveSettings => "SaveSettings",
+            AppEvent::Rebind(_) => "Rebind",
+            AppEvent::StepTimeWarp(_) => "StepTimeWarp",
+        }
+    }
+}
 
+
+/// everything about the running game which doesn't depend on a window or graphics backend, so the
+/// same state can be driven by the real event loop or headlessly
 pub struct AppState {
-    pub window: GlutinWindow,
-    pub menu_state: Option<MenuState>,
-    pub mission_state: Option<MissionState>,
+    /// set when the app wants to exit, the event loop closes the window in response
+    pub should_close: bool,
+    /// size of the window as of the last resize input, used for hit testing
+    pub window_size: [f64; 2],
+    pub scenes: SceneStack,
+    /// maps buttons to the actions scenes respond to
+    pub bindings: Bindings,
+    /// the direction the left stick is currently pushed, if far enough to count as a d-pad press
+    pub stick_direction: Option<Action>,
+    /// subsystems outside of the menu and mission which react to app events
+    pub subscribers: Vec<Box<dyn Subscribe>>,
+    /// turns variable update intervals into fixed simulation steps
+    pub clock: FixedTimestep,
+    /// number of simulation steps taken since startup
+    pub tick: u64,
+    /// all randomness in the game has to come from generators seeded from this, so that a seed and
+    /// a list of inputs determine everything. recorded so sessions can be replayed
+    pub seed: u64,
+    /// seeds missions started without a seed of their own
+    pub rng: GameRng,
+    /// writes every live input to a file, if recording
+    pub recorder: Option<Recorder>,
+    /// plays back recorded inputs in place of live input, if replaying
+    pub replay: Option<Replay>,
+    /// timing and state diagnostics, toggled with Action::ToggleOverlay
+    pub overlay: DebugOverlay,
+    /// developer commands, toggled with Action::ToggleConsole. takes all keyboard input while open
+    pub console: Console,
+    /// simulated seconds per real second. scales the time scenes are updated by, the number of
+    /// steps stays the same. always between MIN_TIME_WARP and MAX_TIME_WARP
+    pub time_warp: f64,
+    /// set by Action::Screenshot. whatever draws the frame saves it and clears this
+    pub screenshot_requested: bool,
+    /// settings as changed on the options screen, starting from the launch config
+    pub config: Config,
+    /// where settings and keybindings are saved when they change. None to leave the files alone,
+    /// e.g. when headless
+    pub config_path: Option<PathBuf>,
+    pub bindings_path: Option<PathBuf>,
+    /// settings changed since the config file was last written, e.g. while a slider is dragged
+    unsaved_settings: Vec<Setting>,
+    /// set when the fullscreen or resolution setting changes. whatever owns the window applies the
+    /// config to it and clears this
+    pub window_changed: bool,
+    /// the action the next button pressed is bound to, if any. a button bound to Back cancels instead
+    pub rebinding: Option<Action>,
+    /// a button just bound to an action, whose release shouldn't trigger it
+    ignore_release: Option<Button>,
 }
 
 impl AppState {
-    pub fn new(window: GlutinWindow) -> AppState {
+    pub fn new(seed: u64) -> AppState {
         AppState {
-            window,
-            menu_state: Some(MenuState::new(false)),
-            mission_state: None,
-        }
-    }
-
-    pub fn handle(&mut self, event: AppEvent) {
-        match event {
-            AppEvent::Exit => self.window.set_should_close(true),
-            AppEvent::OpenMenu => self.menu_state = Some(MenuState::new(self.mission_state.is_some())),
-            AppEvent::ResumeMission => if self.mission_state.is_some() { self.menu_state = None },
-            AppEvent::NewMission(difficulty) => {
-                self.mission_state = Some(MissionState::Planning {
-                    budget: match difficulty {
-                        MissionDifficulty::Easy => 1000.0,
-                        MissionDifficulty::Medium => 750.0,
-                        MissionDifficulty::Hard => 500.0,
+            should_close: false,
+            window_size: [0.0, 0.0],
+            scenes: SceneStack::new(Scene::Menu(MenuState::new(false))),
+            bindings: Bindings::defaults(),
+            stick_direction: None,
+            subscribers: Vec::new(),
+            clock: FixedTimestep::new(),
+            tick: 0,
+            seed,
+            rng: rng::seeded(seed),
+            recorder: None,
+            replay: None,
+            overlay: DebugOverlay::new(),
+            console: Console::new(),
+            time_warp: 1.0,
+            screenshot_requested: false,
+            config: Config::default(),
+            config_path: None,
+            bindings_path: None,
+            unsaved_settings: Vec::new(),
+            window_changed: false,
+            rebinding: None,
+            ignore_release: None,
+        }
+    }
+
+    /// an app which plays back a recording, seeded, bound and launched the same way as the recorded
+    /// session
+    pub fn replaying(replay: Replay, bus: &mut EventBus) -> AppState {
+        let mut app = AppState::new(replay.header.seed);
+        app.bindings = replay.header.bindings.clone();
+        if let Some(launch) = &replay.header.launch {
+            bus.emit(launch.event());
+        }
+        app.replay = Some(replay);
+        app
+    }
+
+    /// entry point for live input. while a replay is running live input is dropped so that it can't
+    /// change the outcome
+    pub fn input(&mut self, input: Input, bus: &mut EventBus) {
+        if self.replay.is_some() {
+            return
+        }
+        if let Some(ref mut recorder) = self.recorder {
+            if let Err(e) = recorder.record(self.tick, &input) {
+                error!("stopped recording: {}", e);
+                self.recorder = None;
+            }
+        }
+        self.apply(input, bus);
+    }
+
+    fn apply(&mut self, input: Input, bus: &mut EventBus) {
+        match input {
+            Input::Cursor(cursor) => self.scenes.hover(self.window_size, cursor, bus),
+            Input::Press(button) => self.press(button, bus),
+            Input::Release(button) => self.release(button, bus),
+            Input::Axis(args) => self.axis(args, bus),
+            Input::Resize(size) => {
+                self.window_size = size;
+                self.scenes.resize(size);
+            },
+            Input::Scroll(delta) => self.scenes.scroll(delta, bus),
+            Input::Text(text) => if self.console.open {
+                self.console.type_text(&text);
+            } else {
+                self.scenes.text(&text, bus);
+            },
+        }
+        self.handle_events(bus);
+    }
+
+    /// press every action bound to the button. actions for the app itself are handled here, the
+    /// rest go to the scenes
+    fn press(&mut self, button: Button, bus: &mut EventBus) {
+        if let Some(action) = self.rebinding.take() {
+            if self.bindings.actions(button).contains(&Action::Back) {
+                self.cancel_rebind(button);
+            } else {
+                self.rebind(action, button);
+            }
+            return
+        }
+        let actions = self.bindings.actions(button);
+        if self.console.open {
+            if actions.contains(&Action::ToggleConsole) {
+                self.console.open = false;
+                return
+            }
+            if actions.contains(&Action::Screenshot) {
+                self.screenshot_requested = true;
+                return
+            }
+            if let Button::Keyboard(key) = button {
+                match key {
+                    Key::Return => self.submit_command(bus),
+                    Key::Backspace => self.console.backspace(),
+                    Key::Escape => self.console.open = false,
+                    _ => {},
+                }
+                return
+            }
+        }
+        for action in actions {
+            match action {
+                Action::ToggleOverlay => self.overlay.visible = !self.overlay.visible,
+                Action::ToggleConsole => self.console.open = true,
+                Action::Screenshot => self.screenshot_requested = true,
+                _ => self.scenes.press(action, bus),
+            }
+        }
+    }
+
+    /// release every action bound to the button
+    fn release(&mut self, button: Button, bus: &mut EventBus) {
+        if self.ignore_release == Some(button) {
+            self.ignore_release = None;
+            return
+        }
+        // keys released while typing belong to the console, e.g. Return after submitting a command
+        if self.console.open && matches!(button, Button::Keyboard(_)) {
+            return
+        }
+        for action in self.bindings.actions(button) {
+            match action {
+                Action::ToggleOverlay | Action::ToggleConsole | Action::Screenshot => {},
+                _ => self.scenes.release(action, bus),
+            }
+        }
+    }
+
+    /// bind button to action, saving the bindings and showing the outcome on the options screen
+    fn rebind(&mut self, action: Action, button: Button) {
+        self.ignore_release = Some(button);
+        let status = match self.bindings.rebind(action, button) {
+            Ok(()) => {
+                info!("bound {:?} to {:?}", button, action);
+                if let Some(path) = &self.bindings_path {
+                    if let Err(e) = self.bindings.save(path) {
+                        error!("keybindings weren't saved: {}", e);
                     }
-                });
-                self.menu_state = None;
+                }
+                "saved".to_string()
+            },
+            Err(e) => e,
+        };
+        if let Some(Scene::Options(options_state)) = self.scenes.top_mut() {
+            options_state.show_bindings(&self.bindings);
+            options_state.set_status(&status);
+        }
+    }
+
+    /// give up on binding an action, leaving its buttons as they were
+    fn cancel_rebind(&mut self, button: Button) {
+        self.ignore_release = Some(button);
+        if let Some(Scene::Options(options_state)) = self.scenes.top_mut() {
+            options_state.cancel_rebind();
+        }
+    }
+
+    /// apply a setting to everything it affects, and remember to save it
+    fn change_setting(&mut self, setting: Setting) {
+        debug!("changing {:?}", setting);
+        setting.apply(&mut self.config);
+        match setting {
+            Setting::Fullscreen(_) | Setting::Resolution(..) => self.window_changed = true,
+            Setting::UiScale(scale) => {
+                layout::set_ui_scale(scale);
+                self.scenes.resize(self.window_size);
+            },
+            Setting::Colorblind(colorblind) => colors::set_colorblind(colorblind),
+            // the volumes go to Audio through the subscribers
+            Setting::MasterVolume(_) | Setting::MusicVolume(_) | Setting::SfxVolume(_) => {},
+        }
+        self.unsaved_settings.retain(|unsaved| unsaved.field() != setting.field());
+        self.unsaved_settings.push(setting);
+    }
+
+    /// write any settings changed since last time to the config file
+    fn save_settings(&mut self) {
+        if self.unsaved_settings.is_empty() {
+            return
+        }
+        if let Some(path) = &self.config_path {
+            if let Err(e) = Config::save_settings(path, &self.unsaved_settings) {
+                error!("{:?} weren't saved: {}", self.unsaved_settings, e);
             }
         }
+        self.unsaved_settings.clear();
     }
 
-    pub fn window_size(&self) -> [f64; 2] {
-        let WindowSize { width, height } = self.window.size();
-        [width, height]
+    /// save the running mission to its slot, named after its difficulty and seed until it's given
+    /// another name on the load screen. returns what happened, for the player
+    fn save_mission(&mut self) -> Result<String, String> {
+        let dir = self.config.saves.clone();
+        let mission = self.scenes.mission_mut().ok_or_else(|| "there's no mission".to_string())?;
+        let name = mission.slot.clone()
+            .unwrap_or_else(|| format!("{:?} {}", mission.difficulty, mission.seed).to_lowercase());
+        let path = saves::save(&dir, &name, mission)?;
+        info!("saved the mission to {}", path.display());
+        mission.slot = Some(name.clone());
+        Ok(format!("saved as {}", name))
     }
-}
 
-impl Hover for AppState {
-    fn hover(&mut self, window_size: [f64; 2], cursor: [f64; 2]) -> Option<AppEvent> {
-        match (&mut self.menu_state, &mut self.mission_state) {
-            (Some(ref mut menu_state), Some(_)) | (Some(ref mut menu_state), None) => menu_state.hover(window_size, cursor),
-            (None, Some(ref mut mission_state)) => mission_state.hover(window_size, cursor),
-            (None, None) => None
+    /// start a loaded mission in place of everything open, or show why it couldn't be loaded
+    fn load_mission(&mut self, loaded: Result<MissionState, String>) {
+        match loaded {
+            Ok(mission) => {
+                info!("loaded a {:?} mission from {}", mission.difficulty, mission.slot.as_deref().unwrap_or("a save"));
+                self.scenes.unwind();
+                self.scenes.replace(Scene::Mission(mission));
+            },
+            Err(e) => {
+                error!("{}", e);
+                if let Some(Scene::Load(load_state)) = self.scenes.top_mut() {
+                    load_state.set_status(&e);
+                }
+            },
         }
     }
-}
 
-impl Press for AppState {
-    fn press(&mut self, button: Button) -> Option<AppEvent> {
-        if let Button::Keyboard(Key::Escape) = button {
-            None
-        } else {
-            match (&mut self.menu_state, &mut self.mission_state) {
-                (Some(ref mut menu_state), Some(_)) | (Some(ref mut menu_state), None) => menu_state.press(button),
-                (None, Some(ref mut mission_state)) => mission_state.press(button),
-                (None, None) => None
+    /// run the line typed into the console
+    fn submit_command(&mut self, bus: &mut EventBus) {
+        let line = self.console.submit();
+        if line.trim().is_empty() {
+            return
+        }
+        info!("console: {}", line);
+        match Command::parse(&line).and_then(|command| self.execute(command, bus)) {
+            Ok(output) => self.console.print(&output),
+            Err(e) => self.console.print(&e),
+        }
+        self.handle_events(bus);
+    }
+
+    /// carry out a console command, returning what to print
+    pub fn execute(&mut self, command: Command, bus: &mut EventBus) -> Result<String, String> {
+        match command {
+            Command::NewMission(difficulty, seed) => {
+                bus.emit(AppEvent::NewMission(difficulty, seed));
+                Ok(format!("starting a {:?} mission", difficulty))
+            },
+            Command::SaveMission => self.save_mission(),
+            Command::SetBudget(amount) => match self.scenes.mission_mut() {
+                Some(mission) => match &mut mission.phase {
+                    MissionPhase::Planning { budget } => {
+                        *budget = amount;
+                        mission.update_labels();
+                        Ok(format!("budget set to {}", amount))
+                    },
+                    MissionPhase::Execution { .. } => Err("the mission is past planning".to_string()),
+                },
+                None => Err("there's no mission".to_string()),
+            },
+            Command::Goto(Destination::Menu(submenu)) => {
+                if let Some(Scene::Mission(_)) = self.scenes.top() {
+                    self.handle(AppEvent::OpenMenu, bus);
+                }
+                match self.scenes.top_mut() {
+                    Some(Scene::Menu(menu_state)) => {
+                        menu_state.open(submenu);
+                        Ok("opened the menu".to_string())
+                    },
+                    _ => Err("the menu couldn't be opened".to_string()),
+                }
+            },
+            Command::Goto(Destination::Options) => {
+                self.handle(AppEvent::OpenOptions, bus);
+                Ok("opened the options".to_string())
+            },
+            Command::Goto(Destination::Load) => {
+                self.handle(AppEvent::OpenLoadScreen, bus);
+                Ok("opened the saved missions".to_string())
+            },
+            Command::Goto(Destination::Mission) => if self.scenes.mission().is_some() {
+                self.handle(AppEvent::ResumeMission, bus);
+                Ok("resumed the mission".to_string())
+            } else {
+                Err("there's no mission".to_string())
+            },
+            Command::PlaySound(sound) => {
+                bus.emit(AppEvent::PlaySound(sound));
+                Ok(format!("playing {:?}", sound))
+            },
+            Command::TimeWarp(factor) => {
+                self.set_time_warp(factor);
+                Ok(format!("time warp set to {}x", self.time_warp))
+            },
+            Command::Help => Ok(console::HELP.to_string()),
+        }
+    }
+
+    /// pass analog input to the scenes, and let the left stick navigate like the d-pad
+    fn axis(&mut self, args: ControllerAxisArgs, bus: &mut EventBus) {
+        self.scenes.axis(args, bus);
+        if args.axis == gamepad::AXIS_LEFT_Y {
+            let direction = if args.position < -STICK_THRESHOLD {
+                Some(Action::Up)
+            } else if args.position > STICK_THRESHOLD {
+                Some(Action::Down)
+            } else {
+                None
+            };
+            if direction != self.stick_direction {
+                if let Some(action) = self.stick_direction {
+                    self.scenes.release(action, bus);
+                }
+                if let Some(action) = direction {
+                    self.scenes.press(action, bus);
+                }
+                self.stick_direction = direction;
             }
         }
     }
+
+    /// drain the bus, dispatching each event (including any emitted in response) in order
+    pub fn handle_events(&mut self, bus: &mut EventBus) {
+        while let Some(event) = bus.pop() {
+            self.handle(event, bus);
+        }
+    }
+
+    /// change the time warp, clamped to the range it's allowed
+    fn set_time_warp(&mut self, factor: f64) {
+        self.time_warp = factor.clamp(MIN_TIME_WARP, MAX_TIME_WARP);
+        info!("time warp set to {}x", self.time_warp);
+    }
+
+    pub fn handle(&mut self, event: AppEvent, bus: &mut EventBus) {
+        debug!("handling {:?}", event);
+        self.overlay.event(&event);
+        match &event {
+            AppEvent::Exit => {
+                self.save_settings();
+                self.should_close = true;
+            },
+            AppEvent::OpenMenu => {
+                let active_mission = self.scenes.mission().is_some();
+                self.scenes.push(Scene::Menu(MenuState::new(active_mission)));
+            },
+            AppEvent::ResumeMission => if self.scenes.mission().is_some() {
+                while let Some(Scene::Menu(_)) = self.scenes.top() {
+                    self.scenes.pop();
+                }
+            },
+            AppEvent::NewMission(difficulty, seed) => {
+                let (difficulty, seed) = (*difficulty, seed.unwrap_or_else(|| self.rng.gen()));
+                info!("starting a {:?} mission with seed {}", difficulty, seed);
+                // starting a mission abandons everything open before it, including any old mission
+                self.scenes.unwind();
+                self.scenes.replace(Scene::Mission(MissionState::new(difficulty, seed)));
+            }
+            AppEvent::SaveMission => {
+                let text = match self.save_mission() {
+                    Ok(saved) => saved,
+                    Err(e) => {
+                        error!("{}", e);
+                        "save failed".to_string()
+                    },
+                };
+                if let Some(Scene::Menu(menu_state)) = self.scenes.top_mut() {
+                    menu_state.set_text("save_mission", text);
+                }
+            },
+            AppEvent::OpenLoadScreen => self.scenes.push(Scene::Load(LoadState::new(self.config.saves.clone()))),
+            AppEvent::CloseLoadScreen => while let Some(Scene::Load(_)) = self.scenes.top() {
+                self.scenes.pop();
+            },
+            AppEvent::LoadMission(path) => self.load_mission(saves::load(path)),
+            AppEvent::RestoreMission(slot, save) => self.load_mission(saves::restore(slot.clone(), save)),
+            AppEvent::RenamedSave(from, to) => if let Some(mission) = self.scenes.mission_mut() {
+                if mission.slot.as_ref() == Some(from) {
+                    mission.slot = Some(to.clone());
+                }
+            },
+            AppEvent::PlaySound(_) => {},
+            AppEvent::OpenOptions => {
+                let options_state = OptionsState::new(&self.config, &self.bindings);
+                self.scenes.push(Scene::Options(options_state));
+            },
+            AppEvent::CloseOptions => {
+                self.rebinding = None;
+                self.save_settings();
+                while let Some(Scene::Options(_)) = self.scenes.top() {
+                    self.scenes.pop();
+                }
+            },
+            AppEvent::ChangeSetting(setting) => self.change_setting(*setting),
+            AppEvent::SaveSettings => self.save_settings(),
+            AppEvent::Rebind(action) => self.rebinding = Some(*action),
+            AppEvent::StepTimeWarp(up) => {
+                let factor = if *up { self.time_warp * TIME_WARP_STEP } else { self.time_warp / TIME_WARP_STEP };
+                self.set_time_warp(factor);
+            },
+        }
+        for subscriber in self.subscribers.iter_mut() {
+            subscriber.notify(&event, bus);
+        }
+    }
 }
 
-impl Release for AppState {
-    fn release(&mut self, button: Button) -> Option<AppEvent> {
-        match (&mut self.menu_state, &mut self.mission_state) {
-            (Some(ref mut menu_state), Some(_)) | (Some(ref mut menu_state), None) => menu_state.release(button),
-            (None, Some(ref mut mission_state)) => mission_state.release(button),
-            (None, None) => None
+
+impl Update for AppState {
+    fn update(&mut self, dt: f64, bus: &mut EventBus) {
+        for _ in 0..self.clock.advance(dt) {
+            // recorded inputs go in at the same point between steps as they originally did
+            if let Some(ref mut replay) = self.replay {
+                let inputs = replay.due(self.tick);
+                if replay.finished() {
+                    self.replay = None;
+                }
+                for input in inputs {
+                    self.apply(input, bus);
+                }
+            }
+            self.tick += 1;
+            self.overlay.step();
+            self.scenes.update(TIMESTEP * self.time_warp, bus);
+            // handle events between steps so each step sees the effects of the previous one
+            self.handle_events(bus);
         }
     }
 }
 
 impl Render for AppState {
-    fn render(&self, c: &Context, gl: &mut GlGraphics) {
-        if let Some(ref menu_state) = self.menu_state {
-            menu_state.render(&c, gl);
-        } else if let Some(ref mission_state) = self.mission_state {
-            mission_state.render(&c, gl);
+    fn render<G: Backend>(&self, c: &Context, g: &mut G) {
+        self.scenes.render(c, g);
+        if self.console.open {
+            self.console.render(c, g);
+        }
+        if self.overlay.visible {
+            self.overlay.render(c, g, &self.scenes);
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::RwLock;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use image::RgbaImage;
+
+use crate::fonts::Font;
+use crate::images::Image;
+use crate::sounds::Sound;
+use crate::sounds::SoundData;
+
+
+lazy_static! {
+    static ref ASSETS: RwLock<Option<Arc<Assets>>> = RwLock::new(None);
+}
+
+/// the revision of the installed assets, readable without taking the lock
+static REVISION: AtomicU64 = AtomicU64::new(0);
+
+
+/// where asset files are read from
+#[derive(Clone, Debug)]
+pub enum AssetSource {
+    /// files under a directory laid out like assets/
+    Dir(PathBuf),
+    /// files compiled into the executable
+    #[cfg(feature = "embedded-assets")]
+    Embedded,
+}
+
+impl AssetSource {
+    pub fn dir(root: PathBuf) -> Result<AssetSource, String> {
+        if root.is_dir() {
+            Ok(AssetSource::Dir(root))
+        } else {
+            Err(format!("asset root {} is not a directory", root.display()))
+        }
+    }
+
+    /// the assets folder next to (or a few levels above) the working directory
+    #[cfg_attr(feature = "embedded-assets", allow(dead_code))]
+    pub fn find_dir() -> Result<AssetSource, String> {
+        find_folder::Search::ParentsThenKids(3, 3)
+            .for_folder("assets")
+            .map(AssetSource::Dir)
+            .map_err(|e| format!("failed to find the assets folder: {}", e))
+    }
+
+    /// the contents of a file, given its path relative to the asset root
+    pub fn read(&self, file: &str) -> Result<Vec<u8>, String> {
+        match self {
+            AssetSource::Dir(root) => {
+                let path = root.join(file);
+                fs::read(&path).map_err(|e| format!("failed to read {}: {}", path.display(), e))
+            },
+            #[cfg(feature = "embedded-assets")]
+            AssetSource::Embedded => embedded(file)
+                .map(|bytes| bytes.to_vec())
+                .ok_or_else(|| format!("{} is not embedded", file)),
+        }
+    }
+}
+
+
+/// every font, image and sound the game uses, read once at startup. lookups never fail: a missing
+/// font falls back to Roboto, a missing image draws nothing and a missing sound is silent. textures
+/// and glyph caches need a GL context, so they're built from these by the renderer
+#[derive(Clone)]
+pub struct Assets {
+    pub source: AssetSource,
+    /// bumped whenever assets are reloaded, so anything built from them knows to rebuild
+    pub revision: u64,
+    fonts: HashMap<Font, rusttype::Font<'static>>,
+    images: HashMap<Image, RgbaImage>,
+    sounds: HashMap<Sound, SoundData>,
+}
+
+impl Assets {
+    /// load everything from source. only fails if Roboto can't be loaded, since it's the fallback for
+    /// every other font. other missing or broken assets are logged as warnings and skipped
+    pub fn load(source: AssetSource) -> Result<Assets, String> {
+        let mut assets = Assets {
+            source,
+            revision: 0,
+            fonts: HashMap::new(),
+            images: HashMap::new(),
+            sounds: HashMap::new(),
+        };
+        for font in Font::ALL.iter() {
+            match load_font(&assets.source, font.file()) {
+                Ok(loaded) => {
+                    debug!("loaded {}", font.file());
+                    assets.fonts.insert(*font, loaded);
+                },
+                Err(e) if *font == Font::Regular => return Err(e),
+                Err(e) => warn!("{}, falling back to Roboto", e),
+            }
+        }
+        for image in Image::ALL.iter() {
+            match load_image(&assets.source, image.file()) {
+                Ok(loaded) => {
+                    debug!("loaded {}", image.file());
+                    assets.images.insert(*image, loaded);
+                },
+                Err(e) => warn!("{}, it won't be drawn", e),
+            }
+        }
+        for sound in Sound::ALL.iter() {
+            match load_sound(&assets.source, sound.file()) {
+                Ok(loaded) => {
+                    debug!("loaded {}", sound.file());
+                    assets.sounds.insert(*sound, loaded);
+                },
+                Err(e) => warn!("{}, it will be silent", e),
+            }
+        }
+        info!("loaded assets from {:?}", assets.source);
+        Ok(assets)
+    }
+
+    /// load whichever assets come from file again, keeping the previous version of any that fail
+    #[cfg(debug_assertions)]
+    pub fn reload(&mut self, file: &str) {
+        for font in Font::ALL.iter().filter(|font| font.file() == file) {
+            match load_font(&self.source, file) {
+                Ok(loaded) => {
+                    info!("reloaded {}", file);
+                    self.fonts.insert(*font, loaded);
+                },
+                Err(e) => warn!("{}, keeping the previous version", e),
+            }
+        }
+        for image in Image::ALL.iter().filter(|image| image.file() == file) {
+            match load_image(&self.source, file) {
+                Ok(loaded) => {
+                    info!("reloaded {}", file);
+                    self.images.insert(*image, loaded);
+                },
+                Err(e) => warn!("{}, keeping the previous version", e),
+            }
+        }
+        for sound in Sound::ALL.iter().filter(|sound| sound.file() == file) {
+            match load_sound(&self.source, file) {
+                Ok(loaded) => {
+                    info!("reloaded {}", file);
+                    self.sounds.insert(*sound, loaded);
+                },
+                Err(e) => warn!("{}, keeping the previous version", e),
+            }
+        }
+        self.revision += 1;
+    }
+
+    pub fn font(&self, font: Font) -> &rusttype::Font<'static> {
+        self.fonts.get(&font).unwrap_or_else(|| &self.fonts[&Font::Regular])
+    }
+
+    pub fn image(&self, image: Image) -> Option<&RgbaImage> {
+        self.images.get(&image)
+    }
+
+    pub fn sound(&self, sound: Sound) -> Option<&SoundData> {
+        self.sounds.get(&sound)
+    }
+}
+
+
+fn load_font(source: &AssetSource, file: &str) -> Result<rusttype::Font<'static>, String> {
+    let bytes = source.read(file)?;
+    rusttype::Font::from_bytes(bytes).map_err(|e| format!("failed to parse font {}: {}", file, e))
+}
+
+fn load_image(source: &AssetSource, file: &str) -> Result<RgbaImage, String> {
+    let bytes = source.read(file)?;
+    image::load_from_memory(&bytes)
+        .map(|image| image.to_rgba())
+        .map_err(|e| format!("failed to decode image {}: {}", file, e))
+}
+
+fn load_sound(source: &AssetSource, file: &str) -> Result<SoundData, String> {
+    let data = SoundData::new(source.read(file)?);
+    // decode the header now so a broken file is reported at startup instead of when it's played
+    rodio::Decoder::new(Cursor::new(data.clone()))
+        .map_err(|e| format!("failed to decode sound {}: {}", file, e))?;
+    Ok(data)
+}
+
+/// files compiled in with the embedded-assets feature, by path relative to the asset root. files
+/// which aren't in the repo (like the soundtrack) are left out and fall back like missing files
+#[cfg(feature = "embedded-assets")]
+fn embedded(file: &str) -> Option<&'static [u8]> {
+    match file {
+        "fonts/Roboto-Regular.ttf" => Some(include_bytes!("../assets/fonts/Roboto-Regular.ttf")),
+        "fonts/House On Mars.ttf" => Some(include_bytes!("../assets/fonts/House On Mars.ttf")),
+        "images/mars.png" => Some(include_bytes!("../assets/images/mars.png")),
+        "sounds/tap-muted.wav" => Some(include_bytes!("../assets/sounds/tap-muted.wav")),
+        _ => None,
+    }
+}
+
+
+/// make assets available through get. called at startup, before anything is drawn or played, and
+/// again whenever they're reloaded
+pub fn install(assets: Assets) {
+    let revision = assets.revision;
+    *ASSETS.write().unwrap() = Some(Arc::new(assets));
+    REVISION.store(revision, Ordering::Release);
+}
+
+pub fn get() -> Arc<Assets> {
+    ASSETS.read().unwrap().clone().expect("assets used before being installed")
+}
+
+/// the revision of the installed assets, for caches of things measured from them to check cheaply
+pub fn revision() -> u64 {
+    REVISION.load(Ordering::Acquire)
+}
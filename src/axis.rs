@@ -0,0 +1,9 @@
+use piston::input::ControllerAxisArgs;
+
+use crate::events::EventBus;
+
+
+/// trait for types which respond to analog controller input, i.e. sticks and triggers
+pub trait Axis {
+    fn axis(&mut self, args: ControllerAxisArgs, bus: &mut EventBus);
+}
\ No newline at end of file
@@ -0,0 +1,212 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::app::MissionDifficulty;
+
+
+pub const USAGE: &str = "usage: mars [options]
+
+display and logging (overrides the config file):
+    --config <file>             read launch config from file instead of the user's config.ron
+    --fullscreen                fill the screen (default)
+    --windowed                  open a window of --resolution instead
+    --resolution <w>x<h>        window size when windowed, e.g. 1280x720
+    --opengl <versions>         OpenGL versions to try in order (default 4.5,3.2,2.1)
+    --vsync, --no-vsync         wait for vertical sync
+    --max-fps <n>               cap on frames rendered per second
+    --ups <n>                   update events per second
+    --assets <dir>              load assets from dir
+    --log-level <level>         log at error, warn, info (default), debug, trace or off
+    --log <module>=<level>      log a module at a different level, e.g. mars::scene=debug
+    --log-file <file>           append the log to file as well as stderr
+    --screenshots <dir>         save screenshots (F12) in dir (default screenshots)
+    --saves <dir>               save and load missions in dir (default the user's data directory)
+    --ui-scale <n>              make menus and text n times their usual size, from 0.5 to 1.5
+                                (default 1)
+
+launch:
+    --new-mission <difficulty>  skip the menu and start an easy, medium or hard mission
+    --seed <n>                  seed all randomness with n instead of a random seed, including
+                                the mission started by --new-mission
+    --load <save>               skip the menu and load a saved mission, by file or by name
+
+testing:
+    --headless [script]         run a script of inputs without a window, GPU or audio. the script
+                                can be left out when replaying
+    --record <file>             record every input to file
+    --replay <file>             play back a recording instead of live input
+    --help                      print this and exit";
+
+
+/// everything that can be set on the command line. anything left out is None, so it falls back to
+/// the config file
+#[derive(Clone, Debug, Default)]
+pub struct Args {
+    pub config: Option<PathBuf>,
+    pub fullscreen: Option<bool>,
+    pub resolution: Option<(u32, u32)>,
+    pub opengl: Option<Vec<String>>,
+    pub vsync: Option<bool>,
+    pub max_fps: Option<u64>,
+    pub ups: Option<u64>,
+    pub assets: Option<PathBuf>,
+    pub log_level: Option<String>,
+    pub log_filters: Vec<(String, String)>,
+    pub log_file: Option<PathBuf>,
+    pub screenshots: Option<PathBuf>,
+    pub saves: Option<PathBuf>,
+    pub ui_scale: Option<f64>,
+    pub new_mission: Option<MissionDifficulty>,
+    pub seed: Option<u64>,
+    pub load: Option<PathBuf>,
+    pub headless: bool,
+    pub script: Option<PathBuf>,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Args {
+    /// parse arguments, not including the program name
+    pub fn parse<I: Iterator<Item=String>>(args: I) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        let mut args = args.peekable();
+        while let Some(flag) = args.next() {
+            match flag.as_str() {
+                "--config" => parsed.config = Some(value(&flag, args.next())?.into()),
+                "--fullscreen" => parsed.fullscreen = Some(true),
+                "--windowed" => parsed.fullscreen = Some(false),
+                "--resolution" => parsed.resolution = Some(resolution(&value(&flag, args.next())?)?),
+                "--opengl" => {
+                    let versions = value(&flag, args.next())?;
+                    parsed.opengl = Some(versions.split(',').map(|version| version.trim().to_string()).collect());
+                },
+                "--vsync" => parsed.vsync = Some(true),
+                "--no-vsync" => parsed.vsync = Some(false),
+                "--max-fps" => parsed.max_fps = Some(number(&flag, args.next())?),
+                "--ups" => parsed.ups = Some(number(&flag, args.next())?),
+                "--assets" => parsed.assets = Some(value(&flag, args.next())?.into()),
+                "--log-level" => parsed.log_level = Some(value(&flag, args.next())?),
+                "--log" => {
+                    let filter = value(&flag, args.next())?;
+                    let mut parts = filter.splitn(2, '=');
+                    match (parts.next(), parts.next()) {
+                        (Some(module), Some(level)) => parsed.log_filters.push((module.to_string(), level.to_string())),
+                        _ => return Err(format!("--log expects <module>=<level>, got {}", filter)),
+                    }
+                },
+                "--log-file" => parsed.log_file = Some(value(&flag, args.next())?.into()),
+                "--screenshots" => parsed.screenshots = Some(value(&flag, args.next())?.into()),
+                "--saves" => parsed.saves = Some(value(&flag, args.next())?.into()),
+                "--ui-scale" => parsed.ui_scale = Some(number(&flag, args.next())?),
+                "--new-mission" => parsed.new_mission = Some(difficulty(&value(&flag, args.next())?)?),
+                "--seed" => parsed.seed = Some(number(&flag, args.next())?),
+                "--load" => parsed.load = Some(value(&flag, args.next())?.into()),
+                "--headless" => {
+                    parsed.headless = true;
+                    if args.peek().is_some_and(|next| !next.starts_with("--")) {
+                        parsed.script = args.next().map(PathBuf::from);
+                    }
+                },
+                "--record" => parsed.record = Some(value(&flag, args.next())?.into()),
+                "--replay" => parsed.replay = Some(value(&flag, args.next())?.into()),
+                "--help" | "-h" => parsed.help = true,
+                _ => return Err(format!("unknown argument {}", flag)),
+            }
+        }
+        if parsed.new_mission.is_some() && parsed.load.is_some() {
+            return Err("--new-mission and --load can't be used together".to_string());
+        }
+        if parsed.headless && parsed.script.is_none() && parsed.replay.is_none() {
+            return Err("--headless requires a script path".to_string());
+        }
+        Ok(parsed)
+    }
+}
+
+
+fn value(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("{} requires a value", flag))
+}
+
+fn number<T: FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = self::value(flag, value)?;
+    value.parse().map_err(|_| format!("{} expects a number, got {}", flag, value))
+}
+
+fn resolution(value: &str) -> Result<(u32, u32), String> {
+    let invalid = || format!("--resolution expects <width>x<height>, got {}", value);
+    let mut parts = value.split('x');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(w), Some(h), None) => Ok((w.parse().map_err(|_| invalid())?, h.parse().map_err(|_| invalid())?)),
+        _ => Err(invalid()),
+    }
+}
+
+fn difficulty(value: &str) -> Result<MissionDifficulty, String> {
+    match value {
+        "easy" => Ok(MissionDifficulty::Easy),
+        "medium" => Ok(MissionDifficulty::Medium),
+        "hard" => Ok(MissionDifficulty::Hard),
+        _ => Err(format!("--new-mission expects easy, medium or hard, got {}", value)),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn flags_set_their_fields() {
+        let args = parse(&[
+            "--windowed",
+            "--resolution", "1280x720",
+            "--opengl", "3.2, 2.1",
+            "--seed", "18446744073709551615",
+            "--new-mission", "hard",
+            "--log", "mars::scene=debug",
+            "--ui-scale", "1.5",
+        ]).unwrap();
+        assert_eq!(args.fullscreen, Some(false));
+        assert_eq!(args.resolution, Some((1280, 720)));
+        assert_eq!(args.opengl, Some(vec!["3.2".to_string(), "2.1".to_string()]));
+        assert_eq!(args.seed, Some(u64::MAX));
+        assert_eq!(args.new_mission, Some(MissionDifficulty::Hard));
+        assert_eq!(args.log_filters, vec![("mars::scene".to_string(), "debug".to_string())]);
+        assert_eq!(args.ui_scale, Some(1.5));
+        assert_eq!(args.vsync, None);
+        assert!(!args.headless);
+    }
+
+    #[test]
+    fn headless_takes_a_script_unless_a_flag_follows() {
+        let args = parse(&["--headless", "script.ron", "--seed", "1"]).unwrap();
+        assert_eq!(args.script, Some(PathBuf::from("script.ron")));
+        assert_eq!(args.seed, Some(1));
+
+        let args = parse(&["--replay", "session.txt", "--headless", "--seed", "1"]).unwrap();
+        assert!(args.headless);
+        assert_eq!(args.script, None);
+        assert_eq!(args.replay, Some(PathBuf::from("session.txt")));
+    }
+
+    #[test]
+    fn bad_arguments_are_refused() {
+        assert_eq!(parse(&["--fast"]).unwrap_err(), "unknown argument --fast");
+        assert_eq!(parse(&["--seed"]).unwrap_err(), "--seed requires a value");
+        assert_eq!(parse(&["--seed", "-1"]).unwrap_err(), "--seed expects a number, got -1");
+        assert_eq!(parse(&["--resolution", "1280"]).unwrap_err(), "--resolution expects <width>x<height>, got 1280");
+        assert_eq!(parse(&["--log", "debug"]).unwrap_err(), "--log expects <module>=<level>, got debug");
+        assert_eq!(parse(&["--new-mission", "extreme"]).unwrap_err(), "--new-mission expects easy, medium or hard, got extreme");
+        assert_eq!(
+            parse(&["--new-mission", "easy", "--load", "base camp"]).unwrap_err(),
+            "--new-mission and --load can't be used together",
+        );
+        assert_eq!(parse(&["--headless"]).unwrap_err(), "--headless requires a script path");
+    }
+}
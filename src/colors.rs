@@ -1,10 +1,46 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 
 use graphics::color::hex;
 use graphics::types::Color;
 
 
 lazy_static! {
-    pub static ref BLACK: Color = hex("000000");
-    pub static ref RED: Color = hex("9e1316");
-    pub static ref MARS: Color = hex("aa4337");
+    static ref STANDARD: Palette = Palette {
+        black: hex("000000"),
+        red: hex("9e1316"),
+        mars: hex("aa4337"),
+    };
+    /// red and the highlight are told apart by hue, which some players can't see. this swaps them for
+    /// colors which differ in brightness as well
+    static ref COLORBLIND: Palette = Palette {
+        black: hex("000000"),
+        red: hex("d55e00"),
+        mars: hex("56b4e9"),
+    };
+}
+
+static COLORBLIND_ENABLED: AtomicBool = AtomicBool::new(false);
+
+
+/// the colors everything is drawn with
+pub struct Palette {
+    pub black: Color,
+    /// text and outlines
+    pub red: Color,
+    /// whatever's hovered or focused
+    pub mars: Color,
+}
+
+/// switch between the standard and colorblind palettes. takes effect from the next frame
+pub fn set_colorblind(colorblind: bool) {
+    COLORBLIND_ENABLED.store(colorblind, Ordering::Relaxed);
+}
+
+pub fn palette() -> &'static Palette {
+    if COLORBLIND_ENABLED.load(Ordering::Relaxed) {
+        &COLORBLIND
+    } else {
+        &STANDARD
+    }
 }
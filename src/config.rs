@@ -0,0 +1,298 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use log::LevelFilter;
+use opengl_graphics::OpenGL;
+use ron::Value;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::cli::Args;
+use crate::logging;
+
+
+/// the range ui_scale is kept in, the same as the options screen offers
+pub const MIN_UI_SCALE: f64 = 0.5;
+pub const MAX_UI_SCALE: f64 = 1.5;
+
+
+/// how the game launches. read from a RON file in the platform config directory, with command line
+/// flags taking precedence. fields missing from the file keep their defaults, e.g.
+/// `(fullscreen: false, resolution: (1280, 720))`
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    pub fullscreen: bool,
+    /// window size when not fullscreen
+    pub resolution: (u32, u32),
+    /// OpenGL versions to try creating a context with, in order. the renderer only needs 2.1, newer
+    /// versions are preferred where the driver has them
+    pub opengl: Vec<String>,
+    pub vsync: bool,
+    pub max_fps: u64,
+    /// update events per second. the simulation itself always steps at a fixed rate
+    pub ups: u64,
+    /// directory to load assets from instead of the nearest assets folder
+    pub assets: Option<PathBuf>,
+    /// level logged by any module without a filter of its own
+    pub log_level: String,
+    /// level by module path, e.g. `{"mars::scene": "debug"}`
+    pub log_filters: BTreeMap<String, String>,
+    /// file to append the log to, as well as stderr
+    pub log_file: Option<PathBuf>,
+    /// directory screenshots are saved in
+    pub screenshots: PathBuf,
+    /// directory missions are saved in, one file per save slot
+    pub saves: PathBuf,
+    /// multiplies the size of text and everything else in menus, on top of scaling with the window
+    pub ui_scale: f64,
+    /// every sound's volume is multiplied by this, from 0 to 1
+    pub master_volume: f32,
+    pub music_volume: f32,
+    /// sound effects, i.e. everything but music
+    pub sfx_volume: f32,
+    /// draw with colors which can be told apart without seeing red and green
+    pub colorblind: bool,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            fullscreen: true,
+            resolution: (1280, 720),
+            opengl: vec!["4.5".to_string(), "3.2".to_string(), "2.1".to_string()],
+            vsync: false,
+            max_fps: 140,
+            ups: 240,
+            assets: None,
+            log_level: "info".to_string(),
+            log_filters: BTreeMap::new(),
+            log_file: None,
+            screenshots: PathBuf::from("screenshots"),
+            saves: dirs::data_dir().map(|dir| dir.join("mars").join("saves")).unwrap_or_else(|| PathBuf::from("saves")),
+            ui_scale: 1.0,
+            master_volume: 1.0,
+            music_volume: 0.5,
+            sfx_volume: 0.5,
+            colorblind: false,
+        }
+    }
+}
+
+impl Config {
+    /// the user's config file in the platform config directory
+    pub fn user_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("mars").join("config.ron"))
+    }
+
+    pub fn load(path: &Path) -> Result<Config, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        ron::de::from_str(&text).map_err(|e| format!("failed to parse {}: {}", path.display(), e))
+    }
+
+    /// change settings in the config file at path, keeping everything else in the file as it is.
+    /// only the fields the file already set and the changed ones are written, so the rest keep
+    /// following the defaults. flags given on the command line this session aren't written back
+    pub fn save_settings(path: &Path, settings: &[Setting]) -> Result<(), String> {
+        let text = if path.exists() {
+            fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?
+        } else {
+            "()".to_string()
+        };
+        let mut config: Config = ron::de::from_str(&text).map_err(|e| format!("failed to parse {}: {}", path.display(), e))?;
+        let mut fields: BTreeSet<String> = match ron::de::from_str(&text) {
+            Ok(Value::Map(map)) => map.keys().filter_map(|key| key.clone().into_rust().ok()).collect(),
+            _ => BTreeSet::new(),
+        };
+        for setting in settings {
+            setting.apply(&mut config);
+            fields.insert(setting.field().to_string());
+        }
+
+        let mut text = "(\n".to_string();
+        for field in fields.iter() {
+            if let Some(value) = config.field(field)? {
+                text.push_str(&format!("    {}: {},\n", field, value));
+            }
+        }
+        text.push_str(")\n");
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+        }
+        fs::write(path, text).map_err(|e| format!("failed to write {}: {}", path.display(), e))
+    }
+
+    /// the field with this name as RON, or None for a name which isn't a field
+    fn field(&self, name: &str) -> Result<Option<String>, String> {
+        let value = match name {
+            "fullscreen" => ron::ser::to_string(&self.fullscreen),
+            "resolution" => ron::ser::to_string(&self.resolution),
+            "opengl" => ron::ser::to_string(&self.opengl),
+            "vsync" => ron::ser::to_string(&self.vsync),
+            "max_fps" => ron::ser::to_string(&self.max_fps),
+            "ups" => ron::ser::to_string(&self.ups),
+            "assets" => ron::ser::to_string(&self.assets),
+            "log_level" => ron::ser::to_string(&self.log_level),
+            "log_filters" => ron::ser::to_string(&self.log_filters),
+            "log_file" => ron::ser::to_string(&self.log_file),
+            "screenshots" => ron::ser::to_string(&self.screenshots),
+            "saves" => ron::ser::to_string(&self.saves),
+            "ui_scale" => ron::ser::to_string(&self.ui_scale),
+            "master_volume" => ron::ser::to_string(&self.master_volume),
+            "music_volume" => ron::ser::to_string(&self.music_volume),
+            "sfx_volume" => ron::ser::to_string(&self.sfx_volume),
+            "colorblind" => ron::ser::to_string(&self.colorblind),
+            _ => return Ok(None),
+        };
+        value.map(Some).map_err(|e| e.to_string())
+    }
+
+    /// the config file named on the command line, else the user's config file if there is one, else
+    /// the defaults. flags are applied on top of whichever it is
+    pub fn resolve(args: &Args) -> Result<Config, String> {
+        let mut config = match &args.config {
+            Some(path) => Config::load(path)?,
+            None => match Config::user_path().filter(|path| path.exists()) {
+                Some(path) => Config::load(&path)?,
+                None => Config::default(),
+            },
+        };
+        if let Some(fullscreen) = args.fullscreen {
+            config.fullscreen = fullscreen;
+        }
+        if let Some(resolution) = args.resolution {
+            config.resolution = resolution;
+        }
+        if let Some(opengl) = &args.opengl {
+            config.opengl = opengl.clone();
+        }
+        if let Some(vsync) = args.vsync {
+            config.vsync = vsync;
+        }
+        if let Some(max_fps) = args.max_fps {
+            config.max_fps = max_fps;
+        }
+        if let Some(ups) = args.ups {
+            config.ups = ups;
+        }
+        if let Some(assets) = &args.assets {
+            config.assets = Some(assets.clone());
+        }
+        if let Some(level) = &args.log_level {
+            config.log_level = level.clone();
+        }
+        config.log_filters.extend(args.log_filters.iter().cloned());
+        if let Some(log_file) = &args.log_file {
+            config.log_file = Some(log_file.clone());
+        }
+        if let Some(screenshots) = &args.screenshots {
+            config.screenshots = screenshots.clone();
+        }
+        if let Some(saves) = &args.saves {
+            config.saves = saves.clone();
+        }
+        if let Some(ui_scale) = args.ui_scale {
+            config.ui_scale = ui_scale;
+        }
+        if config.ui_scale.is_nan() {
+            return Err("ui scale must be a number".to_string());
+        }
+        config.ui_scale = config.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        config.opengl_versions()?;
+        Ok(config)
+    }
+
+    /// the default log level and each module's filter
+    pub fn log_levels(&self) -> Result<(LevelFilter, Vec<(String, LevelFilter)>), String> {
+        let filters = self.log_filters.iter()
+            .map(|(module, level)| logging::level(level).map(|level| (module.clone(), level)))
+            .collect::<Result<_, _>>()?;
+        Ok((logging::level(&self.log_level)?, filters))
+    }
+
+    pub fn opengl_versions(&self) -> Result<Vec<OpenGL>, String> {
+        if self.opengl.is_empty() {
+            return Err("at least one OpenGL version is required".to_string());
+        }
+        self.opengl.iter()
+            .map(|version| version.parse().map_err(|_| format!("unsupported OpenGL version {}", version)))
+            .collect()
+    }
+}
+
+
+/// a single change made on the options screen
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Setting {
+    MasterVolume(f32),
+    MusicVolume(f32),
+    SfxVolume(f32),
+    Fullscreen(bool),
+    /// window size when not fullscreen
+    Resolution(u32, u32),
+    UiScale(f64),
+    Colorblind(bool),
+}
+
+impl Setting {
+    /// name of the Config field the setting changes
+    pub fn field(self) -> &'static str {
+        match self {
+            Setting::MasterVolume(_) => "master_volume",
+            Setting::MusicVolume(_) => "music_volume",
+            Setting::SfxVolume(_) => "sfx_volume",
+            Setting::Fullscreen(_) => "fullscreen",
+            Setting::Resolution(..) => "resolution",
+            Setting::UiScale(_) => "ui_scale",
+            Setting::Colorblind(_) => "colorblind",
+        }
+    }
+
+    pub fn apply(self, config: &mut Config) {
+        match self {
+            Setting::MasterVolume(volume) => config.master_volume = volume,
+            Setting::MusicVolume(volume) => config.music_volume = volume,
+            Setting::SfxVolume(volume) => config.sfx_volume = volume,
+            Setting::Fullscreen(fullscreen) => config.fullscreen = fullscreen,
+            Setting::Resolution(w, h) => config.resolution = (w, h),
+            Setting::UiScale(scale) => config.ui_scale = scale,
+            Setting::Colorblind(colorblind) => config.colorblind = colorblind,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::process;
+
+    use super::*;
+
+    #[test]
+    fn only_changed_settings_are_added_to_the_file() {
+        let dir = env::temp_dir().join(format!("mars-config-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("config.ron");
+
+        Config::save_settings(&path, &[Setting::MasterVolume(0.25)]).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "(\n    master_volume: 0.25,\n)\n");
+
+        fs::write(&path, "(resolution: (800, 600), log_filters: {\"mars::scene\": \"debug\"}, ui_scale: 0.5)").unwrap();
+        Config::save_settings(&path, &[Setting::Fullscreen(false), Setting::UiScale(1.2)]).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        for absent in ["opengl", "saves", "log_level", "master_volume"] {
+            assert!(!text.contains(absent), "{} was written to\n{}", absent, text);
+        }
+        let config = Config::load(&path).unwrap();
+        assert!(!config.fullscreen);
+        assert_eq!(config.resolution, (800, 600));
+        assert_eq!(config.log_filters.get("mars::scene").map(String::as_str), Some("debug"));
+        assert_eq!(config.ui_scale, 1.2);
+        assert_eq!(config.opengl, Config::default().opengl);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
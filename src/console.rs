@@ -0,0 +1,212 @@
+use std::collections::VecDeque;
+
+use graphics::Context;
+use graphics::rectangle;
+use graphics::Transformed;
+
+use crate::app::MissionDifficulty;
+use crate::colors;
+use crate::fonts::Font;
+use crate::menu::SubMenu;
+use crate::render::Backend;
+use crate::sounds::Sound;
+
+
+const FONT_SIZE: u32 = 16;
+const LINE_HEIGHT: f64 = 22.0;
+const PADDING: f64 = 10.0;
+/// fraction of the window height the console covers
+const HEIGHT: f64 = 0.4;
+/// lines of history kept
+const HISTORY: usize = 100;
+const BACKGROUND: [f32; 4] = [0.0, 0.0, 0.0, 0.85];
+
+pub const HELP: &str = "commands:
+    mission new <easy|medium|hard> [seed]
+    mission save
+    budget set <amount>
+    goto menu [new|options]
+    goto load
+    goto mission
+    goto options
+    sound play <tap-muted|holst>
+    timewarp <factor>
+    help";
+
+
+/// a drop-down console for typing commands which jump straight to a state, for testing
+pub struct Console {
+    pub open: bool,
+    input: String,
+    /// commands entered and their output, oldest first
+    history: VecDeque<String>,
+}
+
+impl Console {
+    pub fn new() -> Console {
+        Console { open: false, input: String::new(), history: VecDeque::new() }
+    }
+
+    pub fn type_text(&mut self, text: &str) {
+        // the console key types a character as well as toggling the console
+        self.input.extend(text.chars().filter(|ch| !ch.is_control() && *ch != '`' && *ch != '~'));
+    }
+
+    pub fn backspace(&mut self) {
+        self.input.pop();
+    }
+
+    /// take the typed line, echoing it into the history
+    pub fn submit(&mut self) -> String {
+        let line = std::mem::take(&mut self.input);
+        self.print(&format!("> {}", line));
+        line
+    }
+
+    /// add output to the history, a line at a time
+    pub fn print(&mut self, text: &str) {
+        for line in text.lines() {
+            if self.history.len() == HISTORY {
+                self.history.pop_front();
+            }
+            self.history.push_back(line.to_string());
+        }
+    }
+
+    pub fn render<G: Backend>(&self, c: &Context, g: &mut G) {
+        let [w, h] = c.get_view_size();
+        let height = (h * HEIGHT).floor();
+        rectangle(BACKGROUND, [0.0, 0.0, w, height], c.transform, g);
+
+        // prompt on the bottom line, history above it newest first, until it runs off the top
+        let mut y = height - PADDING;
+        g.draw_text(colors::palette().mars, FONT_SIZE, &format!("> {}_", self.input), Font::Regular, c.transform.trans(PADDING, y));
+        for line in self.history.iter().rev() {
+            y -= LINE_HEIGHT;
+            if y < LINE_HEIGHT {
+                break
+            }
+            g.draw_text(colors::palette().red, FONT_SIZE, line, Font::Regular, c.transform.trans(PADDING, y));
+        }
+    }
+}
+
+
+/// where goto takes the player
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Destination {
+    /// the menu, at the given submenu or the main menu for None
+    Menu(Option<SubMenu>),
+    /// the running mission
+    Mission,
+    /// the options screen, over whatever's showing
+    Options,
+    /// the saved missions, over whatever's showing
+    Load,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+    /// difficulty and seed, None for a seed from the session
+    NewMission(MissionDifficulty, Option<u64>),
+    /// save the running mission to its slot
+    SaveMission,
+    SetBudget(f32),
+    Goto(Destination),
+    PlaySound(Sound),
+    TimeWarp(f64),
+    Help,
+}
+
+impl Command {
+    pub fn parse(line: &str) -> Result<Command, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["mission", "new", difficulty] => Ok(Command::NewMission(parse_difficulty(difficulty)?, None)),
+            ["mission", "new", difficulty, seed] => {
+                let seed = seed.parse().map_err(|_| format!("{} isn't a seed", seed))?;
+                Ok(Command::NewMission(parse_difficulty(difficulty)?, Some(seed)))
+            },
+            ["mission", "save"] => Ok(Command::SaveMission),
+            ["budget", "set", amount] => match amount.parse::<f32>() {
+                Ok(amount) if amount.is_finite() && amount >= 0.0 => Ok(Command::SetBudget(amount)),
+                _ => Err(format!("budget has to be a number no less than 0, not {}", amount)),
+            },
+            ["goto", "menu"] => Ok(Command::Goto(Destination::Menu(None))),
+            ["goto", "menu", submenu] => match *submenu {
+                "new" => Ok(Command::Goto(Destination::Menu(Some(SubMenu::NewMission)))),
+                // options used to be a submenu, and is still reachable from the main menu
+                "options" => Ok(Command::Goto(Destination::Options)),
+                _ => Err(format!("unknown menu {}", submenu)),
+            },
+            ["goto", "mission"] => Ok(Command::Goto(Destination::Mission)),
+            ["goto", "options"] => Ok(Command::Goto(Destination::Options)),
+            ["goto", "load"] => Ok(Command::Goto(Destination::Load)),
+            ["sound", "play", sound] => match *sound {
+                "tap-muted" => Ok(Command::PlaySound(Sound::TapMuted)),
+                "holst" => Ok(Command::PlaySound(Sound::Holst)),
+                _ => Err(format!("unknown sound {}", sound)),
+            },
+            ["timewarp", factor] => match factor.parse::<f64>() {
+                Ok(factor) if factor.is_finite() && factor > 0.0 => Ok(Command::TimeWarp(factor)),
+                _ => Err(format!("time warp has to be a positive number, not {}", factor)),
+            },
+            ["help"] => Ok(Command::Help),
+            _ => Err(format!("unknown command {}, try help", line.trim())),
+        }
+    }
+}
+
+fn parse_difficulty(difficulty: &str) -> Result<MissionDifficulty, String> {
+    match difficulty {
+        "easy" => Ok(MissionDifficulty::Easy),
+        "medium" => Ok(MissionDifficulty::Medium),
+        "hard" => Ok(MissionDifficulty::Hard),
+        _ => Err(format!("unknown difficulty {}", difficulty)),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_are_parsed() {
+        assert_eq!(Command::parse("mission new easy"), Ok(Command::NewMission(MissionDifficulty::Easy, None)));
+        assert_eq!(
+            Command::parse("  mission new hard 18446744073709551615 "),
+            Ok(Command::NewMission(MissionDifficulty::Hard, Some(u64::MAX))),
+        );
+        assert_eq!(Command::parse("mission save"), Ok(Command::SaveMission));
+        assert_eq!(Command::parse("budget set 250.5"), Ok(Command::SetBudget(250.5)));
+        assert_eq!(Command::parse("goto menu new"), Ok(Command::Goto(Destination::Menu(Some(SubMenu::NewMission)))));
+        assert_eq!(Command::parse("goto load"), Ok(Command::Goto(Destination::Load)));
+        assert_eq!(Command::parse("goto options"), Ok(Command::Goto(Destination::Options)));
+        assert_eq!(Command::parse("goto menu options"), Ok(Command::Goto(Destination::Options)));
+        assert_eq!(Command::parse("budget set 0"), Ok(Command::SetBudget(0.0)));
+        assert_eq!(Command::parse("sound play holst"), Ok(Command::PlaySound(Sound::Holst)));
+        assert_eq!(Command::parse("timewarp 0.5"), Ok(Command::TimeWarp(0.5)));
+        assert_eq!(Command::parse("help"), Ok(Command::Help));
+    }
+
+    #[test]
+    fn bad_commands_are_refused() {
+        assert_eq!(Command::parse("mission new extreme"), Err("unknown difficulty extreme".to_string()));
+        assert_eq!(Command::parse("mission new easy -1"), Err("-1 isn't a seed".to_string()));
+        for amount in ["-1", "inf", "NaN", "lots"] {
+            assert_eq!(
+                Command::parse(&format!("budget set {}", amount)),
+                Err(format!("budget has to be a number no less than 0, not {}", amount)),
+            );
+        }
+        assert_eq!(Command::parse("goto menu load"), Err("unknown menu load".to_string()));
+        assert_eq!(Command::parse("launch  rockets"), Err("unknown command launch  rockets, try help".to_string()));
+        for factor in ["0", "-2", "inf", "NaN", "fast"] {
+            assert_eq!(
+                Command::parse(&format!("timewarp {}", factor)),
+                Err(format!("time warp has to be a positive number, not {}", factor)),
+            );
+        }
+    }
+}
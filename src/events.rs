@@ -0,0 +1,32 @@
+use std::collections::VecDeque;
+
+use crate::app::AppEvent;
+
+
+/// queue of events emitted by handlers. any handler can emit any number of events, AppState drains
+/// the queue and dispatches every event to each subsystem, which may emit further events in response
+#[derive(Default)]
+pub struct EventBus {
+    queue: VecDeque<AppEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> EventBus {
+        EventBus { queue: VecDeque::new() }
+    }
+
+    pub fn emit(&mut self, event: AppEvent) {
+        self.queue.push_back(event);
+    }
+
+    /// take the oldest pending event, if any
+    pub fn pop(&mut self) -> Option<AppEvent> {
+        self.queue.pop_front()
+    }
+}
+
+
+/// trait for subsystems which react to events emitted elsewhere in the app
+pub trait Subscribe {
+    fn notify(&mut self, event: &AppEvent, bus: &mut EventBus);
+}
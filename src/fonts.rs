@@ -1,10 +1,79 @@
-use std::sync::Mutex;
+use std::cell::RefCell;
+use std::collections::HashMap;
 
-use opengl_graphics::GlyphCache;
-use opengl_graphics::TextureSettings;
+use graphics::types::FontSize;
+use rusttype::Scale;
 
+use crate::assets;
 
-lazy_static! {
-    pub static ref FONT: Mutex<GlyphCache<'static>> = Mutex::new(GlyphCache::new(find_folder::Search::ParentsThenKids(3, 3).for_folder("fonts").unwrap().join("Roboto-Regular.ttf"), (), TextureSettings::new()).unwrap());
-    pub static ref TITLE_FONT: Mutex<GlyphCache<'static>> = Mutex::new(GlyphCache::new(find_folder::Search::ParentsThenKids(3, 3).for_folder("fonts").unwrap().join("House On Mars.ttf"), (), TextureSettings::new()).unwrap());
+
+/// measurements kept before the cache starts over, so labels which change as the player types
+/// can't grow it forever
+const MAX_CACHED_WIDTHS: usize = 1024;
+
+
+thread_local! {
+    /// widths already measured, so measuring the same text again doesn't touch the assets. only
+    /// ever used from one thread at a time, so there's no lock
+    static WIDTHS: RefCell<WidthCache> = RefCell::new(WidthCache::default());
+}
+
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Font {
+    Regular,
+    Title,
+}
+
+impl Font {
+    pub const ALL: [Font; 2] = [Font::Regular, Font::Title];
+
+    /// path relative to the asset root
+    pub fn file(self) -> &'static str {
+        match self {
+            Font::Regular => "fonts/Roboto-Regular.ttf",
+            Font::Title => "fonts/House On Mars.ttf",
+        }
+    }
+}
+
+
+/// widths by font, size and text, for the assets revision they were measured from. text is keyed
+/// separately so looking it up doesn't need an owned copy
+#[derive(Default)]
+struct WidthCache {
+    revision: u64,
+    len: usize,
+    widths: HashMap<(Font, FontSize), HashMap<String, f64>>,
+}
+
+/// width in pixels of text drawn at the given size. measured once for each font, size and text, and
+/// again after fonts are reloaded
+pub fn width(font: Font, size: FontSize, text: &str) -> f64 {
+    WIDTHS.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let revision = assets::revision();
+        if cache.revision != revision || cache.len >= MAX_CACHED_WIDTHS {
+            *cache = WidthCache { revision, ..WidthCache::default() };
+        }
+        if let Some(width) = cache.widths.get(&(font, size)).and_then(|widths| widths.get(text)) {
+            return *width
+        }
+        let width = measure(font, size, text);
+        cache.widths.entry((font, size)).or_default().insert(text.to_string(), width);
+        cache.len += 1;
+        width
+    })
+}
+
+/// reads the font metrics directly instead of going through CharacterCache::width, which uploads a
+/// texture per glyph and so needs a GL context
+fn measure(font: Font, size: FontSize, text: &str) -> f64 {
+    let assets = assets::get();
+    let font = assets.font(font);
+    // GlyphCache converts points to pixels the same way
+    let scale = Scale::uniform(((size as f32) * 1.333).round());
+    text.chars()
+        .map(|ch| font.glyph(ch).scaled(scale).h_metrics().advance_width as f64)
+        .sum()
 }
@@ -0,0 +1,154 @@
+// the full layout is only referenced when translating gilrs events
+#![cfg_attr(not(feature = "gamepad"), allow(dead_code))]
+
+#[cfg(feature = "gamepad")]
+use gilrs::Gilrs;
+use piston::input::Button;
+use piston::input::ControllerButton;
+#[cfg(feature = "gamepad")]
+use piston::input::ControllerAxisArgs;
+
+#[cfg(feature = "gamepad")]
+use crate::app::AppState;
+#[cfg(feature = "gamepad")]
+use crate::events::EventBus;
+#[cfg(feature = "gamepad")]
+use crate::replay::Input;
+
+
+// controller button and axis numbers, in the SDL game controller layout that piston's sdl2 backend
+// reports. gamepads read through gilrs are translated into the same numbers so bindings work with
+// either source
+pub const BUTTON_A: u8 = 0;
+pub const BUTTON_B: u8 = 1;
+pub const BUTTON_X: u8 = 2;
+pub const BUTTON_Y: u8 = 3;
+pub const BUTTON_BACK: u8 = 4;
+pub const BUTTON_GUIDE: u8 = 5;
+pub const BUTTON_START: u8 = 6;
+pub const BUTTON_LEFT_STICK: u8 = 7;
+pub const BUTTON_RIGHT_STICK: u8 = 8;
+pub const BUTTON_LEFT_SHOULDER: u8 = 9;
+pub const BUTTON_RIGHT_SHOULDER: u8 = 10;
+pub const BUTTON_DPAD_UP: u8 = 11;
+pub const BUTTON_DPAD_DOWN: u8 = 12;
+pub const BUTTON_DPAD_LEFT: u8 = 13;
+pub const BUTTON_DPAD_RIGHT: u8 = 14;
+
+pub const AXIS_LEFT_X: u8 = 0;
+/// negative is up
+pub const AXIS_LEFT_Y: u8 = 1;
+pub const AXIS_RIGHT_X: u8 = 2;
+/// negative is up
+pub const AXIS_RIGHT_Y: u8 = 3;
+pub const AXIS_LEFT_TRIGGER: u8 = 4;
+pub const AXIS_RIGHT_TRIGGER: u8 = 5;
+
+
+/// a button on any controller, for use in bindings. controller ids are ignored when looking up
+/// bindings, so this is always controller 0
+pub fn controller_button(button: u8) -> Button {
+    Button::Controller(ControllerButton::new(0, button))
+}
+
+
+/// what a controller button is called on the controller itself
+pub fn button_name(button: u8) -> String {
+    let name = match button {
+        BUTTON_A => "A",
+        BUTTON_B => "B",
+        BUTTON_X => "X",
+        BUTTON_Y => "Y",
+        BUTTON_BACK => "Back",
+        BUTTON_GUIDE => "Guide",
+        BUTTON_START => "Start",
+        BUTTON_LEFT_STICK => "LS",
+        BUTTON_RIGHT_STICK => "RS",
+        BUTTON_LEFT_SHOULDER => "LB",
+        BUTTON_RIGHT_SHOULDER => "RB",
+        BUTTON_DPAD_UP => "D-pad Up",
+        BUTTON_DPAD_DOWN => "D-pad Down",
+        BUTTON_DPAD_LEFT => "D-pad Left",
+        BUTTON_DPAD_RIGHT => "D-pad Right",
+        _ => return format!("Pad {}", button),
+    };
+    name.to_string()
+}
+
+
+/// reads gamepads directly, since the glutin window backend doesn't report controller events
+#[cfg(feature = "gamepad")]
+pub struct Gamepads {
+    gilrs: Gilrs,
+}
+
+#[cfg(feature = "gamepad")]
+impl Gamepads {
+    pub fn new() -> Result<Gamepads, String> {
+        Gilrs::new()
+            .map(|gilrs| Gamepads { gilrs })
+            .map_err(|e| format!("failed to open gamepads: {}", e))
+    }
+
+    /// feed every gamepad event since the last poll to the app as piston controller input
+    pub fn poll(&mut self, app: &mut AppState, bus: &mut EventBus) {
+        use gilrs::EventType;
+
+        while let Some(event) = self.gilrs.next_event() {
+            let id = usize::from(event.id) as i32;
+            match event.event {
+                EventType::ButtonPressed(button, _) => if let Some(button) = button_number(button) {
+                    app.input(Input::Press(Button::Controller(ControllerButton::new(id, button))), bus);
+                },
+                EventType::ButtonReleased(button, _) => if let Some(button) = button_number(button) {
+                    app.input(Input::Release(Button::Controller(ControllerButton::new(id, button))), bus);
+                },
+                EventType::AxisChanged(axis, position, _) => if let Some((axis, sign)) = axis_number(axis) {
+                    app.input(Input::Axis(ControllerAxisArgs::new(id, axis, sign * position as f64)), bus);
+                },
+                _ => {},
+            }
+        }
+    }
+}
+
+#[cfg(feature = "gamepad")]
+fn button_number(button: gilrs::Button) -> Option<u8> {
+    use gilrs::Button::*;
+
+    match button {
+        South => Some(BUTTON_A),
+        East => Some(BUTTON_B),
+        West => Some(BUTTON_X),
+        North => Some(BUTTON_Y),
+        Select => Some(BUTTON_BACK),
+        Mode => Some(BUTTON_GUIDE),
+        Start => Some(BUTTON_START),
+        LeftThumb => Some(BUTTON_LEFT_STICK),
+        RightThumb => Some(BUTTON_RIGHT_STICK),
+        LeftTrigger => Some(BUTTON_LEFT_SHOULDER),
+        RightTrigger => Some(BUTTON_RIGHT_SHOULDER),
+        DPadUp => Some(BUTTON_DPAD_UP),
+        DPadDown => Some(BUTTON_DPAD_DOWN),
+        DPadLeft => Some(BUTTON_DPAD_LEFT),
+        DPadRight => Some(BUTTON_DPAD_RIGHT),
+        _ => None,
+    }
+}
+
+/// the axis number along with the sign to apply to the position. gilrs has up as positive on the
+/// sticks, the SDL layout has it as negative
+#[cfg(feature = "gamepad")]
+fn axis_number(axis: gilrs::Axis) -> Option<(u8, f64)> {
+    use gilrs::Axis::*;
+
+    match axis {
+        LeftStickX => Some((AXIS_LEFT_X, 1.0)),
+        LeftStickY => Some((AXIS_LEFT_Y, -1.0)),
+        RightStickX => Some((AXIS_RIGHT_X, 1.0)),
+        RightStickY => Some((AXIS_RIGHT_Y, -1.0)),
+        LeftZ => Some((AXIS_LEFT_TRIGGER, 1.0)),
+        RightZ => Some((AXIS_RIGHT_TRIGGER, 1.0)),
+        _ => None,
+    }
+}
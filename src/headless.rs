@@ -0,0 +1,181 @@
+use std::fs;
+use std::path::Path;
+
+use graphics::Context;
+use graphics::DrawState;
+use graphics::Graphics;
+use graphics::ImageSize;
+use graphics::math::Matrix2d;
+use graphics::types::Color;
+use graphics::types::FontSize;
+use piston::input::Button;
+use serde::Deserialize;
+
+use crate::app::AppState;
+use crate::events::EventBus;
+use crate::fonts::Font;
+use crate::images::Image;
+use crate::render::Backend;
+use crate::render::Render;
+use crate::replay::Input;
+use crate::screenshot;
+use crate::update::TIMESTEP;
+use crate::update::Update;
+
+
+/// window size used until a script resizes it
+const DEFAULT_WINDOW_SIZE: [f64; 2] = [1920.0, 1080.0];
+
+
+/// a single scripted input. scripts are RON lists of these, e.g.
+/// `[Cursor((150.0, 150.0)), Press(Mouse(Left)), Release(Mouse(Left)), Wait(0.5)]`
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub enum Step {
+    Cursor((f64, f64)),
+    Press(Button),
+    Release(Button),
+    Resize((f64, f64)),
+    /// mouse wheel movement, positive y scrolls up
+    Scroll((f64, f64)),
+    /// type text, e.g. into the console
+    Text(String),
+    /// draw the current frame to a PNG at this path
+    Screenshot(String),
+    /// run the simulation (and render) for this many seconds
+    Wait(f64),
+}
+
+pub fn load_script(path: &Path) -> Result<Vec<Step>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    ron::de::from_str(&text).map_err(|e| format!("failed to parse {}: {}", path.display(), e))
+}
+
+/// drive the app through a script without a window or GPU, rendering every simulation step to
+/// NullGraphics so render code is exercised too, then run any replay to the end. stops early if the
+/// app asks to exit. screenshots asked for with Action::Screenshot are saved in screenshots
+pub fn run(app: &mut AppState, bus: &mut EventBus, script: &[Step], screenshots: &Path) {
+    app.input(Input::Resize(DEFAULT_WINDOW_SIZE), bus);
+    for step in script {
+        match step {
+            Step::Cursor((x, y)) => app.input(Input::Cursor([*x, *y]), bus),
+            Step::Press(button) => app.input(Input::Press(*button), bus),
+            Step::Release(button) => app.input(Input::Release(*button), bus),
+            Step::Resize((w, h)) => app.input(Input::Resize([*w, *h]), bus),
+            Step::Scroll((x, y)) => app.input(Input::Scroll([*x, *y]), bus),
+            Step::Text(text) => app.input(Input::Text(text.clone()), bus),
+            Step::Screenshot(path) => save_screenshot(app, Path::new(path)),
+            Step::Wait(seconds) => {
+                let steps = (seconds / TIMESTEP).round() as u64;
+                for _ in 0..steps {
+                    advance(app, bus);
+                }
+            }
+        }
+        app.handle_events(bus);
+        take_screenshot(app, screenshots);
+        if app.should_close {
+            return
+        }
+    }
+    while app.replay.is_some() && !app.should_close {
+        advance(app, bus);
+        take_screenshot(app, screenshots);
+    }
+}
+
+/// save a screenshot in dir if the app asked for one
+fn take_screenshot(app: &mut AppState, dir: &Path) {
+    if app.screenshot_requested {
+        app.screenshot_requested = false;
+        save_screenshot(app, &screenshot::timestamped_path(dir));
+    }
+}
+
+fn save_screenshot(app: &AppState, path: &Path) {
+    match screenshot::save(&screenshot::render(app), path) {
+        Ok(()) => info!("saved a screenshot to {}", path.display()),
+        Err(e) => error!("screenshot failed: {}", e),
+    }
+}
+
+/// advance one simulation step and render the result
+fn advance(app: &mut AppState, bus: &mut EventBus) {
+    app.update(TIMESTEP, bus);
+    app.overlay.frame();
+    let [w, h] = app.window_size;
+    app.render(&Context::new_abs(w, h), &mut NullGraphics);
+}
+
+
+/// texture type for NullGraphics, has no size and no contents
+pub struct NullTexture;
+
+impl ImageSize for NullTexture {
+    fn get_size(&self) -> (u32, u32) {
+        (0, 0)
+    }
+}
+
+/// graphics backend which accepts and discards everything drawn to it
+pub struct NullGraphics;
+
+impl Graphics for NullGraphics {
+    type Texture = NullTexture;
+
+    fn clear_color(&mut self, _color: Color) {}
+
+    fn clear_stencil(&mut self, _value: u8) {}
+
+    fn tri_list<F>(&mut self, _draw_state: &DrawState, _color: &[f32; 4], _f: F)
+        where F: FnMut(&mut dyn FnMut(&[[f32; 2]])) {}
+
+    fn tri_list_uv<F>(&mut self, _draw_state: &DrawState, _color: &[f32; 4], _texture: &NullTexture, _f: F)
+        where F: FnMut(&mut dyn FnMut(&[[f32; 2]], &[[f32; 2]])) {}
+}
+
+impl Backend for NullGraphics {
+    fn draw_text(&mut self, _color: Color, _size: FontSize, _text: &str, _font: Font, _transform: Matrix2d) {}
+
+    fn draw_image(&mut self, _image: Image, _transform: Matrix2d) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::path::PathBuf;
+
+    use piston::input::Key;
+
+    use crate::app::MissionDifficulty;
+    use crate::assets;
+    use crate::assets::AssetSource;
+    use crate::assets::Assets;
+
+    use super::*;
+
+    #[test]
+    fn a_scripted_mission_renders() {
+        let source = AssetSource::dir(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets"));
+        assets::install(source.and_then(Assets::load).unwrap());
+        let mut app = AppState::new(1);
+        let mut bus = EventBus::new();
+        let script = [
+            Step::Resize((640.0, 360.0)),
+            Step::Press(Button::Keyboard(Key::Backquote)),
+            Step::Release(Button::Keyboard(Key::Backquote)),
+            Step::Text("mission new easy 5".to_string()),
+            Step::Press(Button::Keyboard(Key::Return)),
+            Step::Release(Button::Keyboard(Key::Return)),
+            Step::Wait(0.1),
+        ];
+        run(&mut app, &mut bus, &script, &env::temp_dir());
+
+        let mission = app.scenes.mission().expect("the console started a mission");
+        assert_eq!((mission.difficulty, mission.seed), (MissionDifficulty::Easy, 5));
+        assert_eq!(app.tick, 12);
+
+        let frame = screenshot::render(&app);
+        assert_eq!(frame.dimensions(), (640, 360));
+        assert!(frame.pixels().any(|pixel| pixel.data[..3] != [0, 0, 0]), "the frame is blank");
+    }
+}
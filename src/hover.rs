@@ -1,6 +1,6 @@
-use crate::app::AppEvent;
+use crate::events::EventBus;
 
 /// trait for types which should receive cursor move events
 pub trait Hover {
-    fn hover(&mut self, window_size: [f64; 2], cursor: [f64; 2]) -> Option<AppEvent>;
+    fn hover(&mut self, window_size: [f64; 2], cursor: [f64; 2], bus: &mut EventBus);
 }
\ No newline at end of file
@@ -1,8 +1,15 @@
-use opengl_graphics::Texture;
-use opengl_graphics::TextureSettings;
-use rayon::prelude::*;
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Image {
+    Mars,
+}
 
+impl Image {
+    pub const ALL: [Image; 1] = [Image::Mars];
 
-lazy_static! {
-    pub static ref MARS: Texture = Texture::from_path(find_folder::Search::ParentsThenKids(3, 3).for_folder("images").unwrap().join("mars.png"), &TextureSettings::new()).unwrap();
+    /// path relative to the asset root
+    pub fn file(self) -> &'static str {
+        match self {
+            Image::Mars => "images/mars.png",
+        }
+    }
 }
@@ -0,0 +1,131 @@
+use std::sync::RwLock;
+
+
+/// the window size UI measurements are given at. at other sizes everything scales by whichever of
+/// width and height is relatively smaller, so the UI always fits
+pub const REFERENCE_SIZE: [f64; 2] = [1920.0, 1080.0];
+
+
+lazy_static! {
+    static ref UI_SCALE: RwLock<f64> = RwLock::new(1.0);
+}
+
+
+/// multiply every UI measurement by scale, on top of scaling with the window, for players who want
+/// everything bigger or smaller
+pub fn set_ui_scale(scale: f64) {
+    *UI_SCALE.write().unwrap() = scale;
+}
+
+
+/// [x, y, w, h] in pixels from the top left of the window
+pub type Rect = [f64; 4];
+
+
+/// where a child sits within its parent. more can be added as layouts need them
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Anchor {
+    TopRight,
+    BottomLeft,
+}
+
+impl Anchor {
+    /// how far across and down the parent the anchor is, from 0 to 1
+    fn fractions(self) -> [f64; 2] {
+        match self {
+            Anchor::TopRight => [1.0, 0.0],
+            Anchor::BottomLeft => [0.0, 1.0],
+        }
+    }
+}
+
+
+/// the whole window, and how much to scale UI measurements by to fit it
+#[derive(Clone, Copy, Debug)]
+pub struct Screen {
+    pub rect: Rect,
+    pub scale: f64,
+}
+
+impl Screen {
+    pub fn new(window_size: [f64; 2]) -> Screen {
+        let [w, h] = window_size;
+        let [rw, rh] = REFERENCE_SIZE;
+        let scale = (w / rw).min(h / rh) * *UI_SCALE.read().unwrap();
+        Screen { rect: [0.0, 0.0, w, h], scale }
+    }
+
+    /// the same screen, scaled down if need be so that something size big at the reference size
+    /// still fits. for screens which fill the window already, where a larger UI scale would push
+    /// things off the edge
+    pub fn fit(self, size: [f64; 2]) -> Screen {
+        let [_, _, w, h] = self.rect;
+        let [fw, fh] = size;
+        Screen { scale: self.scale.min(w / fw).min(h / fh), ..self }
+    }
+
+    /// a measurement at the reference size, in pixels at this one
+    pub fn px(&self, value: f64) -> f64 {
+        value * self.scale
+    }
+
+    /// a font size at the reference size, at this one. never rounds down to nothing
+    pub fn font(&self, size: u32) -> u32 {
+        ((size as f64 * self.scale).round() as u32).max(1)
+    }
+}
+
+
+/// rect shrunk by padding on every side, never to less than nothing
+pub fn inset(rect: Rect, padding: f64) -> Rect {
+    let [x, y, w, h] = rect;
+    [x + padding, y + padding, (w - padding * 2.0).max(0.0), (h - padding * 2.0).max(0.0)]
+}
+
+/// a rect of the given size placed at anchor within parent. the anchor point of the child lands on
+/// the anchor point of the parent, e.g. BottomLeft puts the child in the bottom left corner
+pub fn anchor(parent: Rect, anchor: Anchor, size: [f64; 2]) -> Rect {
+    let [x, y, w, h] = parent;
+    let [fx, fy] = anchor.fractions();
+    let [cw, ch] = size;
+    [x + (w - cw) * fx, y + (h - ch) * fy, cw, ch]
+}
+
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Direction {
+    Down,
+    Right,
+}
+
+
+/// hands out rects one after another across a parent, each as wide (or tall) as the parent
+pub struct Stack {
+    rect: Rect,
+    direction: Direction,
+    spacing: f64,
+    /// distance from the start of rect to the next child
+    offset: f64,
+}
+
+impl Stack {
+    pub fn new(rect: Rect, direction: Direction, spacing: f64) -> Stack {
+        Stack { rect, direction, spacing, offset: 0.0 }
+    }
+
+    /// the rect for the next child, extending length in the stack's direction
+    pub fn next(&mut self, length: f64) -> Rect {
+        let [x, y, w, h] = self.rect;
+        let rect = match self.direction {
+            Direction::Down => [x, y + self.offset, w, length],
+            Direction::Right => [x + self.offset, y, length, h],
+        };
+        self.offset += length + self.spacing;
+        rect
+    }
+
+    /// leave extra space before the next child
+    pub fn skip(&mut self, length: f64) {
+        self.offset += length;
+    }
+}
@@ -0,0 +1,354 @@
+use std::path::PathBuf;
+
+use graphics::clear;
+use graphics::Context;
+
+use crate::actions::Action;
+use crate::app::AppEvent;
+use crate::colors;
+use crate::events::EventBus;
+use crate::hover::Hover;
+use crate::layout;
+use crate::layout::Direction;
+use crate::layout::Screen;
+use crate::layout::Stack;
+use crate::press::Press;
+use crate::release::Release;
+use crate::render::Backend;
+use crate::render::Render;
+use crate::resize::Resize;
+use crate::saves;
+use crate::saves::Slot;
+use crate::scroll::Scroll;
+use crate::text::Text;
+use crate::widgets;
+use crate::widgets::Button;
+use crate::widgets::Label;
+use crate::widgets::List;
+use crate::widgets::Panel;
+use crate::widgets::TextInput;
+use crate::widgets::Widget;
+use crate::widgets::WidgetEvent;
+
+
+// sizes are at the reference window size, see layout::Screen
+const MARGIN: f64 = 120.0;
+const TITLE_FONT_SIZE: u32 = 64;
+const ROW_SPACING: f64 = 8.0;
+const NAME_WIDTH: f64 = 480.0;
+const BUTTON_WIDTH: f64 = 200.0;
+/// rows beneath the list: the name and rename, the other buttons, and the status
+const ROWS_BELOW: usize = 3;
+
+/// the buttons which act on the selected save, disabled while nothing is selected. load is also
+/// disabled for saves which can't be loaded
+const SLOT_BUTTONS: [&str; 3] = ["load", "rename", "delete"];
+const DELETE: &str = "delete";
+const CONFIRM_DELETE: &str = "really delete?";
+
+
+/// lists the saved missions, and loads, renames or deletes them. loading is reported with
+/// AppEvent::LoadMission, renames and deletes happen here
+pub struct LoadState {
+    panel: Panel,
+    /// where the saves are
+    dir: PathBuf,
+    /// the saves in the list, in order
+    slots: Vec<Slot>,
+    /// index of the selected save
+    selected: Option<usize>,
+    /// delete has been clicked once for the selected save, and the next click deletes it
+    confirming_delete: bool,
+}
+
+impl LoadState {
+    pub fn new(dir: PathBuf) -> LoadState {
+        let panel = Panel::new()
+            .child(Widget::Label(Label::new("title", "load mission", TITLE_FONT_SIZE)))
+            .child(Widget::List(List::new("saves", Vec::new())))
+            .child(Widget::TextInput(TextInput::new("name", "select a save to rename it", saves::MAX_NAME_LEN)))
+            .child(Widget::Button(Button::new("rename", "rename")))
+            .child(Widget::Button(Button::new("load", "load")))
+            .child(Widget::Button(Button::new("delete", DELETE)))
+            .child(Widget::Button(Button::new("back", "back")))
+            .child(Widget::Label(Label::new("status", "", widgets::font_size(widgets::HEIGHT))));
+        let mut load = LoadState { panel, dir, slots: Vec::new(), selected: None, confirming_delete: false };
+        load.refresh(None);
+        load
+    }
+
+    /// read the saves again, selecting the one called selected if it's still there
+    fn refresh(&mut self, selected: Option<&str>) {
+        self.slots = match saves::list(&self.dir) {
+            Ok(slots) => slots,
+            Err(e) => {
+                error!("{}", e);
+                self.set_status(&e);
+                Vec::new()
+            },
+        };
+        let summaries = self.slots.iter().map(Slot::summary).collect();
+        if let Some(Widget::List(list)) = self.panel.find_mut("saves") {
+            list.items = summaries;
+            list.selected = None;
+            list.scroll = 0;
+        }
+        let i = selected.and_then(|name| self.slots.iter().position(|slot| slot.name == name));
+        self.select(i);
+        if self.slots.is_empty() {
+            self.set_status("there are no saved missions yet");
+        }
+    }
+
+    /// select the save at index i, or nothing for None, filling in its name to rename it. a save
+    /// which can't be loaded can still be renamed or deleted, and shows why instead
+    fn select(&mut self, i: Option<usize>) {
+        self.selected = i;
+        self.confirming_delete = false;
+        let name = i.map(|i| self.slots[i].name.clone()).unwrap_or_default();
+        if let Some(Widget::List(list)) = self.panel.find_mut("saves") {
+            list.selected = i;
+        }
+        if let Some(Widget::TextInput(input)) = self.panel.find_mut("name") {
+            input.text = name;
+            input.state.disabled = i.is_none();
+        }
+        for id in SLOT_BUTTONS.iter() {
+            if let Some(Widget::Button(button)) = self.panel.find_mut(id) {
+                button.state.disabled = i.is_none();
+            }
+        }
+        self.set_delete_text(DELETE);
+        let error = i.and_then(|i| self.slots[i].details.as_ref().err().cloned());
+        if let Some(Widget::Button(button)) = self.panel.find_mut("load") {
+            button.state.disabled = i.is_none() || error.is_some();
+        }
+        self.set_status(error.as_deref().unwrap_or(""));
+    }
+
+    fn set_delete_text(&mut self, text: &str) {
+        if let Some(Widget::Button(button)) = self.panel.find_mut("delete") {
+            button.text = text.to_string();
+        }
+    }
+
+    /// id of the widget under the cursor, if any
+    pub fn hovered(&self) -> Option<&str> {
+        self.panel.hovered()
+    }
+
+    /// show a message under the buttons, e.g. why a save couldn't be loaded
+    pub fn set_status(&mut self, status: &str) {
+        if let Some(Widget::Label(label)) = self.panel.find_mut("status") {
+            label.text = status.to_string();
+        }
+    }
+
+    /// rename the selected save to whatever's typed in the name input
+    fn rename(&mut self, bus: &mut EventBus) {
+        let (slot, name) = match (self.selected.map(|i| &self.slots[i]), self.panel.find("name")) {
+            (Some(slot), Some(Widget::TextInput(input))) => (slot.name.clone(), input.text.clone()),
+            _ => return,
+        };
+        if name.trim() == slot {
+            return
+        }
+        match saves::rename(&self.dir, &slot, &name) {
+            Ok(name) => {
+                info!("renamed save {} to {}", slot, name);
+                self.refresh(Some(&name));
+                self.set_status(&format!("renamed {} to {}", slot, name));
+                bus.emit(AppEvent::RenamedSave(slot, name));
+            },
+            Err(e) => self.set_status(&e),
+        }
+    }
+
+    /// load the selected save, unless it's one which can't be loaded
+    fn load(&self, bus: &mut EventBus) {
+        if let Some(slot) = self.selected.map(|i| &self.slots[i]).filter(|slot| slot.details.is_ok()) {
+            bus.emit(AppEvent::LoadMission(slot.path.clone()));
+        }
+    }
+
+    /// delete the selected save, after asking once
+    fn delete(&mut self) {
+        let slot = match self.selected {
+            Some(i) => self.slots[i].name.clone(),
+            None => return,
+        };
+        if !self.confirming_delete {
+            self.confirming_delete = true;
+            self.set_delete_text(CONFIRM_DELETE);
+            self.set_status(&format!("click again to delete {} for good", slot));
+            return
+        }
+        match saves::delete(&self.dir, &slot) {
+            Ok(()) => {
+                info!("deleted save {}", slot);
+                self.refresh(None);
+                self.set_status(&format!("deleted {}", slot));
+            },
+            Err(e) => self.set_status(&e),
+        }
+    }
+
+    fn handle_widget_events(&mut self, bus: &mut EventBus) {
+        for event in self.panel.take_events() {
+            match event {
+                WidgetEvent::Selected("saves", i) => self.select(Some(i)),
+                WidgetEvent::Clicked("load") | WidgetEvent::Confirmed("saves", _) => self.load(bus),
+                WidgetEvent::Clicked("rename") | WidgetEvent::Submitted("name", _) => self.rename(bus),
+                WidgetEvent::Clicked("delete") => self.delete(),
+                WidgetEvent::Clicked("back") => bus.emit(AppEvent::CloseLoadScreen),
+                _ => {},
+            }
+        }
+    }
+}
+
+impl Resize for LoadState {
+    /// the title at the top, the list filling the middle and the name, buttons and status below it
+    fn resize(&mut self, window_size: [f64; 2]) {
+        let screen = Screen::new(window_size);
+        let [x, y, w, h] = layout::inset(screen.rect, screen.px(MARGIN));
+        let row = screen.px(widgets::HEIGHT);
+        let spacing = screen.px(ROW_SPACING);
+
+        let title_height = screen.px(TITLE_FONT_SIZE as f64);
+        let list_height = h - title_height - spacing * 2.0 - (row + spacing) * ROWS_BELOW as f64;
+        let mut rows = Stack::new([x, y, w, h], Direction::Down, spacing);
+        let title = rows.next(title_height);
+        if let Some(Widget::Label(label)) = self.panel.find_mut("title") {
+            label.rect = title;
+            label.size = screen.font(TITLE_FONT_SIZE);
+        }
+        rows.skip(spacing);
+        let list = rows.next(list_height.max(row));
+        if let Some(Widget::List(widget)) = self.panel.find_mut("saves") {
+            widget.rect = list;
+            widget.row_height = row;
+        }
+
+        let lines = [
+            vec![("name", NAME_WIDTH), ("rename", BUTTON_WIDTH)],
+            vec![("load", BUTTON_WIDTH), ("delete", BUTTON_WIDTH), ("back", BUTTON_WIDTH)],
+        ];
+        for line in lines.iter() {
+            let mut columns = Stack::new(rows.next(row), Direction::Right, spacing);
+            for (id, width) in line {
+                let rect = columns.next(screen.px(*width));
+                if let Some(widget) = self.panel.find_mut(id) {
+                    widget.set_rect(rect);
+                }
+            }
+        }
+        let status = rows.next(row);
+        if let Some(Widget::Label(label)) = self.panel.find_mut("status") {
+            label.rect = status;
+            label.size = widgets::font_size(row);
+        }
+    }
+}
+
+impl Render for LoadState {
+    fn render<G: Backend>(&self, c: &Context, g: &mut G) {
+        clear(colors::palette().black, g);
+        self.panel.render(c, g);
+    }
+}
+
+impl Hover for LoadState {
+    fn hover(&mut self, window_size: [f64; 2], cursor: [f64; 2], bus: &mut EventBus) {
+        self.panel.hover(window_size, cursor, bus);
+    }
+}
+
+impl Press for LoadState {
+    fn press(&mut self, action: Action, bus: &mut EventBus) {
+        self.panel.press(action, bus);
+        self.handle_widget_events(bus);
+    }
+}
+
+impl Release for LoadState {
+    fn release(&mut self, action: Action, bus: &mut EventBus) {
+        self.panel.release(action, bus);
+        self.handle_widget_events(bus);
+        if let Action::Back = action {
+            bus.emit(AppEvent::CloseLoadScreen);
+        }
+    }
+}
+
+impl Text for LoadState {
+    fn text(&mut self, text: &str, bus: &mut EventBus) {
+        self.panel.text(text, bus);
+    }
+}
+
+impl Scroll for LoadState {
+    fn scroll(&mut self, delta: [f64; 2], bus: &mut EventBus) {
+        self.panel.scroll(delta, bus);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::process;
+
+    use crate::app::MissionDifficulty;
+    use crate::mission::MissionState;
+
+    use super::*;
+
+    /// a load screen listing two saves, in a directory of its own
+    fn load_state(test: &str) -> LoadState {
+        let dir = env::temp_dir().join(format!("mars-{}-{}", test, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for (name, seed) in [("alpha", 1), ("beta", 2)] {
+            saves::save(&dir, name, &MissionState::new(MissionDifficulty::Easy, seed)).unwrap();
+        }
+        let mut load = LoadState::new(dir);
+        load.resize([1920.0, 1080.0]);
+        load
+    }
+
+    /// press and release each action in turn, returning what the screen asked for besides sounds
+    fn input(load: &mut LoadState, actions: &[Action]) -> Vec<AppEvent> {
+        let mut bus = EventBus::new();
+        for action in actions {
+            load.press(*action, &mut bus);
+            load.release(*action, &mut bus);
+        }
+        let mut events = Vec::new();
+        while let Some(event) = bus.pop() {
+            if !matches!(event, AppEvent::PlaySound(_)) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn down_leaves_the_list_for_the_buttons() {
+        let mut load = load_state("load-down");
+        // into the list, down both rows, then past the name and rename to load
+        let actions = [Action::Down, Action::Down, Action::Down, Action::Down, Action::Down, Action::Down, Action::Confirm];
+        assert_eq!(input(&mut load, &actions), [AppEvent::LoadMission(load.slots[1].path.clone())]);
+        fs::remove_dir_all(&load.dir).unwrap();
+    }
+
+    #[test]
+    fn confirm_loads_the_selected_row() {
+        let mut load = load_state("load-confirm");
+        let actions = [Action::Down, Action::Down, Action::Confirm];
+        assert_eq!(input(&mut load, &actions), [AppEvent::LoadMission(load.slots[0].path.clone())]);
+        // up past the first row leaves the list, wrapping round to back
+        assert_eq!(input(&mut load, &[Action::Up, Action::Confirm]), [AppEvent::CloseLoadScreen]);
+        fs::remove_dir_all(&load.dir).unwrap();
+    }
+}
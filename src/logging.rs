@@ -0,0 +1,31 @@
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use log::LevelFilter;
+
+
+/// parse a level name such as "info" or "debug", or "off"
+pub fn level(name: &str) -> Result<LevelFilter, String> {
+    LevelFilter::from_str(name).map_err(|_| format!("unknown log level {}", name))
+}
+
+/// send log messages to stderr, and to file (appending) if there is one. level applies to every
+/// module without its own entry in filters, which are (module path, level) pairs, e.g.
+/// ("mars::scene", Debug)
+pub fn init(level: LevelFilter, filters: &[(String, LevelFilter)], file: Option<&Path>) -> Result<(), String> {
+    let mut dispatch = fern::Dispatch::new()
+        .format(|out, message, record| {
+            out.finish(format_args!("[{} {}] {}", record.level(), record.target(), message))
+        })
+        .level(level);
+    for (module, level) in filters {
+        dispatch = dispatch.level_for(module.clone(), *level);
+    }
+    dispatch = dispatch.chain(io::stderr());
+    if let Some(path) = file {
+        let file = fern::log_file(path).map_err(|e| format!("failed to open log file {}: {}", path.display(), e))?;
+        dispatch = dispatch.chain(file);
+    }
+    dispatch.apply().map_err(|e| format!("failed to set up logging: {}", e))
+}
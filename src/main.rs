@@ -2,107 +2,359 @@ extern crate glutin_window;
 extern crate graphics;
 #[macro_use]
 extern crate lazy_static;
+#[macro_use]
+extern crate log;
 extern crate opengl_graphics;
 extern crate piston;
 extern crate rayon;
 
+use std::env;
+use std::fs;
+use std::process;
+
 use glutin_window::GlutinWindow;
 use graphics::Context;
-use opengl_graphics::OpenGL;
 use opengl_graphics::GlGraphics;
 use piston::event_loop::Events;
 use piston::event_loop::EventSettings;
+use piston::input::ControllerAxisEvent;
 use piston::input::MouseCursorEvent;
+use piston::input::MouseScrollEvent;
 use piston::input::PressEvent;
 use piston::input::ReleaseEvent;
 use piston::input::ResizeEvent;
 use piston::input::RenderEvent;
+use piston::input::TextEvent;
+use piston::input::UpdateEvent;
 use piston::window::AdvancedWindow;
 use piston::window::Window;
 use piston::window::WindowSettings;
 use piston::window::Size as WindowSize;
 
+mod actions;
 mod app;
+mod assets;
+mod axis;
+mod cli;
 mod colors;
+mod config;
+mod console;
+mod events;
 mod fonts;
+mod gamepad;
+mod headless;
 mod hover;
 mod images;
+mod layout;
+mod load;
+mod logging;
 mod menu;
+mod menus;
 mod mission;
+mod options;
+mod overlay;
 mod press;
+mod raster;
 mod release;
 mod render;
+mod replay;
+mod resize;
+mod rng;
+mod saves;
+mod scene;
+mod screenshot;
+mod scroll;
 mod sounds;
+mod text;
 mod update;
+mod widgets;
+#[cfg(debug_assertions)]
+mod watcher;
 
+use actions::Bindings;
+use app::AppEvent;
 use app::AppState;
-use hover::Hover;
-use press::Press;
-use release::Release;
+use assets::Assets;
+use assets::AssetSource;
+use cli::Args;
+use config::Config;
+use events::EventBus;
+#[cfg(feature = "gamepad")]
+use gamepad::Gamepads;
+use render::GlBackend;
+use render::GlResources;
 use render::Render;
-use sounds::play_sound;
+use replay::Header;
+use replay::Input;
+use replay::Launch;
+use replay::Recorder;
+use replay::Replay;
+use sounds::Audio;
 use sounds::Sound;
+use update::Update;
+#[cfg(debug_assertions)]
+use watcher::AssetWatcher;
 
 
-// TODO: model state changes through events. so the collision detector would just emit a
-// Collision(A, B) event and both the list of planets and the focused state could update as a result
-// these events can look basically the same as the update/render/input events in the base event loop
-// every event is dispatched to each subsystem, they're all allowed to update in response
-
 fn main() {
-    let opengl = OpenGL::V4_5;
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}, see mars --help", e);
+            process::exit(2);
+        },
+    };
+    if args.help {
+        println!("{}", cli::USAGE);
+        return
+    }
+    let mut config = match Config::resolve(&args) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        },
+    };
+    let logged = config.log_levels()
+        .and_then(|(level, filters)| logging::init(level, &filters, config.log_file.as_deref()));
+    if let Err(e) = logged {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+
+    // an explicit asset directory overrides both the nearest assets folder and embedded assets
+    let asset_source = match &config.assets {
+        Some(path) => AssetSource::dir(path.clone()),
+        #[cfg(feature = "embedded-assets")]
+        None => Ok(AssetSource::Embedded),
+        #[cfg(not(feature = "embedded-assets"))]
+        None => AssetSource::find_dir(),
+    };
+    match asset_source.and_then(Assets::load) {
+        Ok(loaded) => assets::install(loaded),
+        Err(e) => {
+            error!("{}", e);
+            process::exit(1);
+        },
+    }
+
+    // launch options skip the main menu. --load takes a save file, or the name of a save in the
+    // saves directory
+    let launch = match (args.new_mission, &args.load) {
+        (Some(difficulty), _) => Some(Launch::NewMission(difficulty, args.seed)),
+        (None, Some(load)) => {
+            let path = if load.exists() { load.clone() } else { saves::path(&config.saves, &load.to_string_lossy()) };
+            match fs::read_to_string(&path) {
+                Ok(save) => Some(Launch::LoadMission { slot: path.file_stem().map(|stem| stem.to_string_lossy().into_owned()), save }),
+                Err(e) => {
+                    error!("failed to read {}: {}", path.display(), e);
+                    process::exit(1);
+                },
+            }
+        },
+        (None, None) => None,
+    };
+
+    // --replay <file> plays back a recording made with --record <file>, seeded, bound, launched and
+    // laid out the way the recording was
+    let mut bus = EventBus::new();
+    let replay = args.replay.as_ref().map(|path| match Replay::load(path) {
+        Ok(replay) => replay,
+        Err(e) => {
+            error!("{}", e);
+            process::exit(1);
+        },
+    });
+    if let Some(replay) = &replay {
+        config.ui_scale = replay.header.ui_scale;
+    }
+    layout::set_ui_scale(config.ui_scale);
+    colors::set_colorblind(config.colorblind);
+
+    let mut app = match replay {
+        Some(replay) => AppState::replaying(replay, &mut bus),
+        None => {
+            let mut app = AppState::new(args.seed.unwrap_or_else(rng::random_seed));
+            // headless runs keep the default keybindings so scripts do the same thing everywhere
+            if !args.headless {
+                if let Some(path) = Bindings::user_path().filter(|path| path.exists()) {
+                    match Bindings::load(&path) {
+                        Ok(bindings) => app.bindings = bindings,
+                        Err(e) => warn!("using default keybindings: {}", e),
+                    }
+                }
+            }
+            if let Some(launch) = &launch {
+                bus.emit(launch.event());
+            }
+            app
+        },
+    };
+    app.config = config.clone();
+    if let Some(path) = &args.record {
+        let launch = match &app.replay {
+            Some(replay) => replay.header.launch.clone(),
+            None => launch,
+        };
+        let header = Header { seed: app.seed, launch, bindings: app.bindings.clone(), ui_scale: config.ui_scale };
+        match Recorder::create(path, &header) {
+            Ok(recorder) => app.recorder = Some(recorder),
+            Err(e) => error!("not recording: {}", e),
+        }
+    }
+    app.handle_events(&mut bus);
+
+    // --headless runs the app against scripted input with no window, GPU or audio
+    if args.headless {
+        let script = match &args.script {
+            Some(path) => headless::load_script(path),
+            None => Ok(Vec::new()),
+        };
+        match script {
+            Ok(script) => headless::run(&mut app, &mut bus, &script, &config.screenshots),
+            Err(e) => {
+                error!("{}", e);
+                process::exit(1);
+            }
+        }
+        return
+    }
+
+    // settings changed on the options screen are saved for next time, along with any keybindings
+    app.config_path = args.config.clone().or_else(Config::user_path);
+    app.bindings_path = Bindings::user_path();
 
-    let mut window: GlutinWindow = WindowSettings::new("mars", [1, 1])
-        .graphics_api(opengl)
-        .fullscreen(true)
-        .build()
-        .expect("failed to create window");
+    // fullscreen starts at 1x1 and grows to fill the screen on the first resize, see below
+    let size = if config.fullscreen { (1, 1) } else { config.resolution };
+    // take the first OpenGL version the driver can create a context for
+    let mut window = None;
+    for opengl in config.opengl_versions().unwrap_or_default() {
+        let settings = WindowSettings::new("mars", [size.0, size.1])
+            .graphics_api(opengl)
+            .fullscreen(config.fullscreen)
+            .vsync(config.vsync);
+        match settings.build::<GlutinWindow>() {
+            Ok(created) => {
+                info!("created an OpenGL {:?} window", opengl);
+                window = Some((created, opengl));
+                break
+            },
+            Err(e) => warn!("failed to create an OpenGL {:?} window, trying the next version: {}", opengl, e),
+        }
+    }
+    let (mut window, opengl) = match window {
+        Some(window) => window,
+        None => {
+            error!("no OpenGL version in {:?} is available", config.opengl);
+            process::exit(1);
+        },
+    };
 
     let mut gl = GlGraphics::new(opengl);
+    let mut resources = GlResources::new();
 
     let mut event_settings = EventSettings::new();
-    event_settings.max_fps = 140;
-    event_settings.ups = 240;
+    event_settings.max_fps = config.max_fps;
+    event_settings.ups = config.ups;
     let mut events = Events::new(event_settings);
 
-    let mut app = AppState::new(window);
+    app.subscribers.push(Box::new(Audio::new(&config)));
+
+    #[cfg(feature = "gamepad")]
+    let mut gamepads = match Gamepads::new() {
+        Ok(gamepads) => Some(gamepads),
+        Err(e) => {
+            warn!("{}", e);
+            None
+        },
+    };
 
-    use std::io::BufReader;
-    use std::fs::File;
+    // dev builds pick up changes to asset files without a restart
+    #[cfg(debug_assertions)]
+    let mut asset_watcher = AssetWatcher::new();
 
-    play_sound(Sound::Holst, 0.5);
+    bus.emit(AppEvent::PlaySound(Sound::Holst));
+    app.handle_events(&mut bus);
 
-    while let Some(e) = events.next(&mut app.window) {
+    while let Some(e) = events.next(&mut window) {
         // we don't know what size the screen is, but with fullscreen(true) we'll get a resize event
         // that sets us to the full size of the screen before getting an event that sets us to the
         // configured size. we take advantage of that event to set the size correctly.
         e.resize(|r| {
-            let [ow, oh] = app.window_size();
+            let [ow, oh] = window_size(&window);
             let [w, h] = r.window_size;
-            if w > ow || h > oh {
-                app.window.set_size([w, h]);
+            if app.config.fullscreen && (w > ow || h > oh) {
+                window.set_size([w, h]);
             }
+            app.input(Input::Resize(window_size(&window)), &mut bus);
         });
 
-        e.mouse_cursor(|c| {
-            let ae = app.hover(app.window_size(), c);
-            if let Some(app_event) = ae { app.handle(app_event) }
-        });
+        e.mouse_cursor(|c| app.input(Input::Cursor(c), &mut bus));
 
-        e.press(|b| {
-            let ae = app.press(b);
-            if let Some(app_event) = ae { app.handle(app_event) }
-        });
+        e.press(|b| app.input(Input::Press(b), &mut bus));
 
-        e.release(|b| {
-            let ae = app.release(b);
-            if let Some(app_event) = ae { app.handle(app_event) }
-        });
+        e.release(|b| app.input(Input::Release(b), &mut bus));
+
+        e.controller_axis(|args| app.input(Input::Axis(args), &mut bus));
+
+        e.mouse_scroll(|delta| app.input(Input::Scroll(delta), &mut bus));
+
+        e.text(|text| app.input(Input::Text(text.to_string()), &mut bus));
+
+        #[cfg(feature = "gamepad")]
+        {
+            if let Some(ref mut gamepads) = gamepads {
+                gamepads.poll(&mut app, &mut bus);
+            }
+        }
+
+        #[cfg(debug_assertions)]
+        {
+            if let Some(ref mut asset_watcher) = asset_watcher {
+                asset_watcher.check();
+            }
+        }
+
+        e.update(|u| app.update(u.dt, &mut bus));
+
+        app.handle_events(&mut bus);
+        if app.should_close {
+            window.set_should_close(true);
+        }
+        if app.window_changed {
+            app.window_changed = false;
+            apply_window_settings(&mut window, &app.config);
+        }
 
         e.render(|r| {
+            app.overlay.frame();
+            resources.sync();
             gl.draw(r.viewport(), |c: Context, gl: &mut GlGraphics| {
-                app.render(&c, gl);
+                app.render(&c, &mut GlBackend { gl, resources: &mut resources });
             });
+            if app.screenshot_requested {
+                app.screenshot_requested = false;
+                let [w, h] = r.draw_size;
+                let path = screenshot::timestamped_path(&config.screenshots);
+                match screenshot::capture_gl(w, h).and_then(|image| screenshot::save(&image, &path)) {
+                    Ok(()) => info!("saved a screenshot to {}", path.display()),
+                    Err(e) => error!("screenshot failed: {}", e),
+                }
+            }
         });
     }
 }
+
+/// switch between fullscreen and windowed, and resize the window when windowed
+fn apply_window_settings(window: &mut GlutinWindow, config: &Config) {
+    let monitor = if config.fullscreen { Some(window.ctx.window().get_current_monitor()) } else { None };
+    window.ctx.window().set_fullscreen(monitor);
+    if !config.fullscreen {
+        window.set_size([config.resolution.0, config.resolution.1]);
+    }
+}
+
+fn window_size(window: &GlutinWindow) -> [f64; 2] {
+    let WindowSize { width, height } = window.size();
+    [width, height]
+}
\ No newline at end of file
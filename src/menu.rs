@@ -1,83 +1,112 @@
-use std::collections::HashMap;
-
-use graphics::character::CharacterCache;
 use graphics::clear;
 use graphics::Context;
-use graphics::ellipse;
-use graphics::image;
-use graphics::text;
+use graphics::rectangle;
 use graphics::Transformed;
-use graphics::types::Color;
-use opengl_graphics::GlGraphics;
-use opengl_graphics::GlyphCache;
-use opengl_graphics::Texture;
-use piston::input::Button;
-use piston::input::Key;
-use piston::input::MouseButton;
 
+use crate::actions::Action;
 use crate::app::AppEvent;
-use crate::app::MissionDifficulty;
+use crate::assets;
 use crate::colors;
+use crate::events::EventBus;
 use crate::fonts;
+use crate::fonts::Font;
 use crate::hover::Hover;
-use crate::images;
+use crate::images::Image;
+use crate::layout;
+use crate::layout::Anchor;
+use crate::layout::Direction;
+use crate::layout::Screen;
+use crate::layout::Stack;
+use crate::menus;
+use crate::menus::Label;
+use crate::menus::MenuAction;
 use crate::press::Press;
 use crate::release::Release;
+use crate::render::Backend;
 use crate::render::Render;
-use crate::sounds::play_sound;
+use crate::resize::Resize;
 use crate::sounds::Sound;
+use crate::text::Text;
+use crate::update::Update;
+use crate::widgets;
 
 
+// sizes are at the reference window size, see layout::Screen
 const MENU_BUTTON_FONT_SIZE: u32 = 32;
+/// thickness of the underline drawn beneath the focused button
+const FOCUS_UNDERLINE: f64 = 3.0;
+/// vertical distance between the tops of buttons
+const BUTTON_SPACING: f64 = 50.0;
+/// extra space above a separated button
+const SEPARATION: f64 = 50.0;
+/// space between the edge of the window and the buttons and title
+const MARGIN: f64 = 160.0;
+const TITLE_FONT_SIZE: u32 = 324;
+/// how tall the title's letters are for each point of font size
+const TITLE_HEIGHT: f64 = 1.25;
+/// the planet hangs off the right and bottom of the window, with its top left corner this far left
+/// of the right edge and down from the top
+const PLANET_OFFSET: [f64; 2] = [1152.0, 108.0];
+const PLANET_SCALE: f64 = 0.75;
+/// drawn over the mission beneath the pause menu, dark enough that the buttons stand out
+const PAUSE_SHADE: [f32; 4] = [0.0, 0.0, 0.0, 0.85];
+/// as many digits as u64::MAX has
+const MAX_SEED_DIGITS: usize = 20;
 
 
 pub struct MenuButton {
-    text: &'static str,
-    pos: [f64; 2],
+    /// id of the menu item the button was made from
+    id: &'static str,
+    label: Label,
+    text: String,
+    action: MenuAction,
+    /// drawn with extra space above
+    separated: bool,
+    /// [x, y, w, h] around the text, for both drawing and hit testing. set by MenuState::layout,
+    /// only when the window is resized or the menu's buttons or their text change
+    rect: [f64; 4],
+    font_size: u32,
     hovered: bool,
+    /// selected for keyboard or controller activation
+    focused: bool,
 }
 
 impl MenuButton {
-    pub fn new(text: &'static str, pos: [f64; 2], hovered: bool) -> MenuButton {
-        MenuButton { text, pos, hovered }
+    pub fn new(id: &'static str, label: Label, text: String, action: MenuAction, separated: bool) -> MenuButton {
+        MenuButton { id, label, text, action, separated, rect: [0.0; 4], font_size: 1, hovered: false, focused: false }
     }
 
-    pub fn contains_point(&self, window_size: [f64; 2], point: [f64; 2]) -> bool {
-        let [w, h] = window_size;
-        let [dx, dy] = self.pos;
-        let [x, y] = [w * 0.1 + dx, w * 0.1 + dy];
-        let [px, py] = point;
-        let mut font = fonts::FONT.lock().unwrap();
-        x < px && px < x + font.width(MENU_BUTTON_FONT_SIZE, self.text).unwrap() && (y - MENU_BUTTON_FONT_SIZE as f64) < py && py < y
+    pub fn contains_point(&self, point: [f64; 2]) -> bool {
+        widgets::contains(self.rect, point)
     }
 }
 
 impl Render for MenuButton {
-    fn render(&self, c: &Context, gl: &mut GlGraphics) {
-        let [w, h] = c.get_view_size();
-        let [dx, dy] = self.pos;
-        let [x, y] = [w * 0.1 + dx, w * 0.1 + dy];
-        let mut font = fonts::FONT.lock().unwrap();
-        text(
+    fn render<G: Backend>(&self, c: &Context, g: &mut G) {
+        // the text sits on the bottom of the rect
+        let [x, y, w, h] = self.rect;
+        let y = y + h;
+        g.draw_text(
             if self.hovered {
-                *colors::MARS
+                colors::palette().mars
             } else {
-                *colors::RED
+                colors::palette().red
             },
-            MENU_BUTTON_FONT_SIZE,
-            self.text,
-            &mut *font,
+            self.font_size,
+            &self.text,
+            Font::Regular,
             c.transform.trans(x, y),
-            gl
         );
+        if self.focused {
+            let underline = FOCUS_UNDERLINE * self.font_size as f64 / MENU_BUTTON_FONT_SIZE as f64;
+            rectangle(colors::palette().mars, [x, y + underline * 2.0, w, underline], c.transform, g);
+        }
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum SubMenu {
     NewMission,
-    LoadMission,
-    Options,
 }
 
 pub struct MenuState {
@@ -89,165 +118,314 @@ pub struct MenuState {
     pub buttons: Vec<MenuButton>,
     /// index of the hovered button (if any), used for detecting presses
     pub hovered: Option<usize>,
+    /// index of the button (if any) that Confirm activates. moved by Up, Down and Next, and follows
+    /// the cursor so keyboard navigation picks up from wherever the mouse left off
+    pub focused: Option<usize>,
     /// indicates that the left mouse button went down while hovering the button at this index. only
     /// consider a button pressed if the cursor is there on both down and up
     pub pressing: Option<usize>,
+    /// digits typed on the new mission menu. empty for a seed drawn from the session
+    pub seed_input: String,
+    /// the window as of the last resize, which the buttons, title and planet are laid out in
+    screen: Screen,
+    /// where the title's baseline starts
+    title: [f64; 2],
+    title_size: u32,
+    /// top left of the planet
+    planet: [f64; 2],
+    planet_scale: f64,
+    /// the assets revision the buttons were measured with. reloading the fonts changes their widths
+    revision: u64,
 }
 
 impl MenuState {
-    fn main_menu_buttons(active_mission: bool) -> Vec<MenuButton> {
-        let mut buttons = Vec::new();
-        let mut y = 0.0;
-        if active_mission {
-            buttons.push(MenuButton::new("resume", [0.0, y], false));
-            y += 50.0;
-        }
-        buttons.push(MenuButton::new("new mission", [0.0, y], false));
-        y += 50.0;
-        buttons.push(MenuButton::new("load mission", [0.0, y], false));
-        y += 50.0;
-        buttons.push(MenuButton::new("options", [0.0, y], false));
-        y += 50.0;
-        buttons.push(MenuButton::new("exit", [0.0, y], false));
-        buttons
-    }
-
-    fn new_mission_menu_buttons() -> Vec<MenuButton> {
-        let mut buttons = Vec::new();
-        let mut y = 0.0;
-        buttons.push(MenuButton::new("easy", [0.0, y], false));
-        y += 50.0;
-        buttons.push(MenuButton::new("medium", [0.0, y], false));
-        y += 50.0;
-        buttons.push(MenuButton::new("hard", [0.0, y], false));
-        y += 100.0;
-        buttons.push(MenuButton::new("back", [0.0, y], false));
-        buttons
-    }
-
-    fn load_mission_menu_buttons() -> Vec<MenuButton> {
-        let mut buttons = Vec::new();
-        // TODO
-        buttons
-    }
-
-    fn options_menu_buttons() -> Vec<MenuButton> {
-        let mut buttons = Vec::new();
-        // TODO
-        buttons
+    /// buttons for the enabled items on a submenu, or the main menu for None
+    fn buttons(&self, submenu: Option<SubMenu>) -> Vec<MenuButton> {
+        menus::items(submenu).into_iter()
+            .filter(|item| (item.enabled)(self))
+            .map(|item| MenuButton::new(item.id, item.label, self.label_text(item.label), item.action, item.separated))
+            .collect()
+    }
+
+    /// place the buttons, title and planet to fit the window. buttons are stacked down from the top
+    /// left, the title sits in the bottom left and the planet on the right. only the buttons follow
+    /// the UI scale, the title and planet would run off the window. the title shrinks if large
+    /// buttons would run into it
+    fn layout(&mut self) {
+        let screen = self.screen;
+        let fitted = screen.fit(layout::REFERENCE_SIZE);
+        let area = layout::inset(screen.rect, fitted.px(MARGIN));
+        let font_size = screen.font(MENU_BUTTON_FONT_SIZE);
+        let height = font_size as f64;
+        let mut stack = Stack::new(area, Direction::Down, screen.px(BUTTON_SPACING) - height);
+        let mut bottom = area[1];
+        for button in self.buttons.iter_mut() {
+            if button.separated {
+                stack.skip(screen.px(SEPARATION));
+            }
+            let [x, y, _, _] = stack.next(height);
+            button.rect = [x, y, fonts::width(Font::Regular, font_size, &button.text), height];
+            button.font_size = font_size;
+            bottom = y + height * 2.0;
+        }
+        let [x, y, _, _] = layout::anchor(area, Anchor::BottomLeft, [0.0, 0.0]);
+        self.title = [x, y];
+        let room = ((y - bottom) / TITLE_HEIGHT).max(1.0) as u32;
+        let [right, top, _, _] = layout::anchor(screen.rect, Anchor::TopRight, [0.0, 0.0]);
+        let [dx, dy] = PLANET_OFFSET;
+        self.planet = [(right - fitted.px(dx)).floor(), top + fitted.px(dy)];
+        self.title_size = fitted.font(TITLE_FONT_SIZE).min(room);
+        self.planet_scale = fitted.px(PLANET_SCALE);
+        self.revision = assets::revision();
+    }
+
+    /// lay the menu out again if the fonts have been reloaded since it was last laid out
+    fn layout_if_stale(&mut self) {
+        if self.revision != assets::revision() {
+            self.layout();
+        }
+    }
+
+    fn label_text(&self, label: Label) -> String {
+        match label {
+            Label::Fixed(text) => text.to_string(),
+            Label::Seed if self.seed_input.is_empty() => "seed: random (type to choose)".to_string(),
+            Label::Seed => format!("seed: {}", self.seed_input),
+        }
+    }
+
+    /// the seed typed on the new mission menu, if any
+    fn seed(&self) -> Option<u64> {
+        self.seed_input.parse().ok()
+    }
+
+    /// whether the current menu shows the seed, so typing digits changes it
+    fn has_seed(&self) -> bool {
+        self.buttons.iter().any(|button| button.label == Label::Seed)
+    }
+
+    fn set_seed_input(&mut self, seed_input: String) {
+        self.seed_input = seed_input;
+        let text = self.label_text(Label::Seed);
+        for button in self.buttons.iter_mut().filter(|button| button.label == Label::Seed) {
+            button.text = text.clone();
+        }
+        self.layout();
+    }
+
+    /// swap in the buttons for a different (sub)menu, nothing is hovered, focused or pressed until
+    /// the cursor moves or the player navigates
+    fn set_buttons(&mut self, buttons: Vec<MenuButton>) {
+        self.buttons = buttons;
+        self.hovered = None;
+        self.focused = None;
+        self.pressing = None;
+        self.layout();
+    }
+
+    /// move focus to the button at index i
+    fn focus(&mut self, i: usize) {
+        for (j, button) in self.buttons.iter_mut().enumerate() {
+            button.focused = i == j;
+        }
+        self.focused = Some(i);
+    }
+
+    /// move focus by offset buttons, wrapping around at either end. with nothing focused yet, moving
+    /// forward starts at the first button and moving back starts at the last
+    fn move_focus(&mut self, offset: isize, bus: &mut EventBus) {
+        let n = self.buttons.len() as isize;
+        if n == 0 {
+            return
+        }
+        let i = match self.focused {
+            Some(i) => (i as isize + offset).rem_euclid(n),
+            None if offset > 0 => 0,
+            None => n - 1,
+        } as usize;
+        if self.focused != Some(i) {
+            bus.emit(AppEvent::PlaySound(Sound::TapMuted));
+        }
+        self.focus(i);
+    }
+
+    /// switch to a submenu, or back to the main menu for None
+    pub fn open(&mut self, submenu: Option<SubMenu>) {
+        self.submenu = submenu;
+        let buttons = self.buttons(submenu);
+        self.set_buttons(buttons);
+    }
+
+    /// do whatever the button at index i does
+    fn activate(&mut self, i: usize, bus: &mut EventBus) {
+        let button = &self.buttons[i];
+        debug!("activated {} in {:?}", button.id, self.submenu);
+        match button.action {
+            MenuAction::ResumeMission => bus.emit(AppEvent::ResumeMission),
+            MenuAction::Open(submenu) => self.open(Some(submenu)),
+            MenuAction::Back => self.open(None),
+            MenuAction::NewMission(difficulty) => bus.emit(AppEvent::NewMission(difficulty, self.seed())),
+            MenuAction::ClearSeed => self.set_seed_input(String::new()),
+            MenuAction::SaveMission => bus.emit(AppEvent::SaveMission),
+            MenuAction::LoadMission => bus.emit(AppEvent::OpenLoadScreen),
+            MenuAction::Options => bus.emit(AppEvent::OpenOptions),
+            MenuAction::Exit => bus.emit(AppEvent::Exit),
+        }
+    }
+
+    /// change the text of the button made from the item with this id, e.g. to show that a save
+    /// worked. it goes back to the item's label when the menu is reopened
+    pub fn set_text(&mut self, id: &str, text: String) {
+        for button in self.buttons.iter_mut().filter(|button| button.id == id) {
+            button.text = text.clone();
+        }
+        self.layout();
+    }
+
+    pub fn hovered_text(&self) -> Option<&str> {
+        self.hovered.map(|i| self.buttons[i].text.as_str())
     }
 
     pub fn new(active_mission: bool) -> MenuState {
-        MenuState {
+        let mut menu = MenuState {
             active_mission,
             submenu: None,
-            buttons: MenuState::main_menu_buttons(active_mission),
+            buttons: Vec::new(),
             hovered: None,
+            focused: None,
             pressing: None,
-        }
+            seed_input: String::new(),
+            screen: Screen::new([0.0, 0.0]),
+            title: [0.0, 0.0],
+            title_size: 1,
+            planet: [0.0, 0.0],
+            planet_scale: 0.0,
+            revision: 0,
+        };
+        menu.open(None);
+        menu
     }
 }
 
 impl Render for MenuState {
-    fn render(
+    fn render<G: Backend>(
         &self,
         c: &Context,
-        gl: &mut GlGraphics
+        g: &mut G
     ) {
-        clear(*colors::BLACK, gl);
-        let [w, h] = c.get_view_size();
-
-        // title and background
-        image(&*images::MARS, c.transform.trans((w * 0.4).floor(), h * 0.1).scale(w / 2560.0, w / 2560.0), gl);
-        {
-            let mut title_font = fonts::TITLE_FONT.lock().unwrap();
-            text(*colors::RED, (h * 0.3) as u32, "MARS", &mut *title_font, c.transform.trans(w * 0.1, h - w * 0.1), gl).unwrap();
+        // the pause menu shows the mission through a shade, the main menu has the planet behind it
+        if self.active_mission {
+            let [w, h] = c.get_view_size();
+            rectangle(PAUSE_SHADE, [0.0, 0.0, w, h], c.transform, g);
+        } else {
+            clear(colors::palette().black, g);
+            let [x, y] = self.planet;
+            g.draw_image(Image::Mars, c.transform.trans(x, y).scale(self.planet_scale, self.planet_scale));
         }
+        let [x, y] = self.title;
+        g.draw_text(colors::palette().red, self.title_size, "MARS", Font::Title, c.transform.trans(x, y));
 
         // menu options
         for button in self.buttons.iter() {
-            button.render(c, gl);
+            button.render(c, g);
         }
     }
 }
 
+impl Resize for MenuState {
+    fn resize(&mut self, window_size: [f64; 2]) {
+        self.screen = Screen::new(window_size);
+        self.layout();
+    }
+}
+
 impl Hover for MenuState {
-    fn hover(&mut self, window_size: [f64; 2], cursor: [f64; 2]) -> Option<AppEvent> {
+    fn hover(&mut self, _window_size: [f64; 2], cursor: [f64; 2], bus: &mut EventBus) {
+        self.layout_if_stale();
         let previously_hovered = self.hovered;
         // unset hovered and then reset it if we're still hovering, this way if we're not hovering
         // it gets unset correctly
         self.hovered = None;
         for (i, button) in self.buttons.iter_mut().enumerate() {
-            if button.contains_point(window_size, cursor) {
+            if button.contains_point(cursor) {
                 button.hovered = true;
                 if previously_hovered != Some(i) {
-                    play_sound(Sound::TapMuted, 0.5);
+                    bus.emit(AppEvent::PlaySound(Sound::TapMuted));
                 }
                 self.hovered = Some(i);
+                self.focused = Some(i);
             } else {
                 button.hovered = false;
             }
         }
-        None
+        if let Some(i) = self.focused {
+            self.focus(i);
+        }
     }
 }
 
 impl Press for MenuState {
-    fn press(&mut self, button: Button) -> Option<AppEvent> {
-        self.pressing = self.hovered;
-        None
+    fn press(&mut self, action: Action, bus: &mut EventBus) {
+        match action {
+            Action::Select => self.pressing = self.hovered,
+            Action::Up => self.move_focus(-1, bus),
+            Action::Down | Action::Next => self.move_focus(1, bus),
+            Action::Erase if self.has_seed() => {
+                let mut seed_input = self.seed_input.clone();
+                seed_input.pop();
+                self.set_seed_input(seed_input);
+            },
+            _ => {},
+        }
+    }
+}
+
+impl Text for MenuState {
+    /// digits typed on a menu showing the seed choose it. digits which would make it too big for a
+    /// u64 are dropped, so the seed shown is always the one used
+    fn text(&mut self, text: &str, _bus: &mut EventBus) {
+        if self.has_seed() {
+            let mut seed_input = self.seed_input.clone();
+            for ch in text.chars().filter(|ch| ch.is_ascii_digit()) {
+                seed_input.push(ch);
+                if seed_input.len() > MAX_SEED_DIGITS || seed_input.parse::<u64>().is_err() {
+                    seed_input.pop();
+                }
+            }
+            if seed_input != self.seed_input {
+                self.set_seed_input(seed_input);
+            }
+        }
     }
 }
 
 impl Release for MenuState {
-    fn release(&mut self, button: Button) -> Option<AppEvent>  {
-        if let Button::Mouse(MouseButton::Left) = button {
-            let pressing = self.pressing;
-            self.pressing = None;
-            if pressing == self.hovered {
-                if let Some(i) = pressing {
-                    match (self.submenu, self.active_mission, i) {
-                        // main menu
-                        (None, true, 0) => return Some(AppEvent::ResumeMission),
-                        (None, true, 1) | (None, false, 0) => {
-                            self.submenu = Some(SubMenu::NewMission);
-                            self.buttons = MenuState::new_mission_menu_buttons();
-                        },
-                        (None, true, 2) | (None, false, 1) => {
-                            self.submenu = Some(SubMenu::LoadMission);
-                            self.buttons = MenuState::load_mission_menu_buttons();
-                        },
-                        (None, true, 3) | (None, false, 2) => {
-                            self.submenu = Some(SubMenu::Options);
-                            self.buttons = MenuState::options_menu_buttons();
-                        },
-                        (None, true, 4) | (None, false, 3) => return Some(AppEvent::Exit),
-                        // new mission menu
-                        (Some(SubMenu::NewMission), _, 0) => return Some(AppEvent::NewMission(MissionDifficulty::Easy)),
-                        (Some(SubMenu::NewMission), _, 1) => return Some(AppEvent::NewMission(MissionDifficulty::Medium)),
-                        (Some(SubMenu::NewMission), _, 2) => return Some(AppEvent::NewMission(MissionDifficulty::Hard)),
-                        (Some(SubMenu::NewMission), am, 3) => {
-                            self.submenu = None;
-                            self.buttons = MenuState::main_menu_buttons(am);
-                        },
-                        // load mission menu
-                        // TODO
-                        // options menu
-                        (Some(SubMenu::Options), _, i) => println!("button {} pressed in options menu", i),
-                        // TODO
-                        _ => {},
+    fn release(&mut self, action: Action, bus: &mut EventBus) {
+        match action {
+            Action::Select => {
+                let pressing = self.pressing;
+                self.pressing = None;
+                if pressing == self.hovered {
+                    if let Some(i) = pressing {
+                        self.activate(i, bus);
                     }
                 }
-            }
-        } else if let Button::Keyboard(Key::Escape) = button {
-            if self.submenu.is_some() {
-                self.submenu = None;
-                self.buttons = MenuState::main_menu_buttons(self.active_mission);
-                return None
+            },
+            Action::Confirm => if let Some(i) = self.focused {
+                self.activate(i, bus);
+            },
+            Action::Back => if self.submenu.is_some() {
+                self.open(None);
             } else {
-                return Some(AppEvent::ResumeMission)
-            }
+                bus.emit(AppEvent::ResumeMission);
+            },
+            _ => {},
         }
-        None
+    }
+}
+
+impl Update for MenuState {
+    fn update(&mut self, _dt: f64, _bus: &mut EventBus) {
+        self.layout_if_stale();
     }
 }
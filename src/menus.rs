@@ -0,0 +1,105 @@
+use crate::app::MissionDifficulty;
+use crate::menu::MenuState;
+use crate::menu::SubMenu;
+
+
+/// what activating a menu item does
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MenuAction {
+    ResumeMission,
+    /// show the item's children
+    Open(SubMenu),
+    /// return to the main menu
+    Back,
+    /// start a mission with the seed typed on the menu, if any
+    NewMission(MissionDifficulty),
+    /// forget the typed seed, so the mission's seed comes from the session
+    ClearSeed,
+    /// save the running mission to its slot
+    SaveMission,
+    /// show the saved missions over the menu
+    LoadMission,
+    /// show the options screen over the menu
+    Options,
+    Exit,
+}
+
+
+/// the text an item shows
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Label {
+    Fixed(&'static str),
+    /// the seed typed on the new mission menu
+    Seed,
+}
+
+
+/// a single entry on a menu. each item carries its own action, so the position it ends up at never
+/// matters
+pub struct MenuItem {
+    /// stays the same when the label changes, for logs and tests
+    pub id: &'static str,
+    pub label: Label,
+    pub action: MenuAction,
+    /// items which aren't enabled are left off the menu, e.g. resume when there's no mission
+    pub enabled: fn(&MenuState) -> bool,
+    /// drawn with extra space above, setting it apart from the items before it
+    pub separated: bool,
+    /// the items shown when this one is opened with MenuAction::Open
+    pub children: Vec<MenuItem>,
+}
+
+impl MenuItem {
+    fn new(id: &'static str, label: Label, action: MenuAction) -> MenuItem {
+        MenuItem { id, label, action, enabled: |_| true, separated: false, children: Vec::new() }
+    }
+
+    fn enabled(mut self, enabled: fn(&MenuState) -> bool) -> MenuItem {
+        self.enabled = enabled;
+        self
+    }
+
+    fn separated(mut self) -> MenuItem {
+        self.separated = true;
+        self
+    }
+
+    fn children(mut self, children: Vec<MenuItem>) -> MenuItem {
+        self.children = children;
+        self
+    }
+}
+
+
+/// the main menu, with each submenu as the children of the item which opens it
+pub fn main_menu() -> Vec<MenuItem> {
+    vec![
+        MenuItem::new("resume", Label::Fixed("resume"), MenuAction::ResumeMission)
+            .enabled(|menu| menu.active_mission),
+        MenuItem::new("save_mission", Label::Fixed("save mission"), MenuAction::SaveMission)
+            .enabled(|menu| menu.active_mission),
+        MenuItem::new("new_mission", Label::Fixed("new mission"), MenuAction::Open(SubMenu::NewMission))
+            .children(vec![
+                MenuItem::new("easy", Label::Fixed("easy"), MenuAction::NewMission(MissionDifficulty::Easy)),
+                MenuItem::new("medium", Label::Fixed("medium"), MenuAction::NewMission(MissionDifficulty::Medium)),
+                MenuItem::new("hard", Label::Fixed("hard"), MenuAction::NewMission(MissionDifficulty::Hard)),
+                MenuItem::new("seed", Label::Seed, MenuAction::ClearSeed).separated(),
+                MenuItem::new("back", Label::Fixed("back"), MenuAction::Back),
+            ]),
+        MenuItem::new("load_mission", Label::Fixed("load mission"), MenuAction::LoadMission),
+        MenuItem::new("options", Label::Fixed("options"), MenuAction::Options),
+        MenuItem::new("exit", Label::Fixed("exit"), MenuAction::Exit),
+    ]
+}
+
+/// the items on a submenu, or on the main menu for None
+pub fn items(submenu: Option<SubMenu>) -> Vec<MenuItem> {
+    let main = main_menu();
+    match submenu {
+        None => main,
+        Some(submenu) => main.into_iter()
+            .find(|item| item.action == MenuAction::Open(submenu))
+            .map(|item| item.children)
+            .unwrap_or_default(),
+    }
+}
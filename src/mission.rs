@@ -1,80 +1,226 @@
-use std::collections::HashMap;
-
 use graphics::clear;
 use graphics::Context;
-use graphics::text;
 use graphics::Transformed;
-use graphics::types::Color;
-use opengl_graphics::GlGraphics;
-use opengl_graphics::GlyphCache;
-use opengl_graphics::Texture;
-use piston::input::Button;
-use piston::input::Key;
+use piston::input::ControllerAxisArgs;
 
+use crate::actions::Action;
 use crate::app::AppEvent;
+use crate::app::MissionDifficulty;
+use crate::axis::Axis;
 use crate::colors;
-use crate::fonts;
+use crate::events::EventBus;
+use crate::fonts::Font;
 use crate::hover::Hover;
+use crate::layout;
+use crate::layout::Anchor;
+use crate::layout::Direction;
+use crate::layout::Screen;
+use crate::layout::Stack;
 use crate::press::Press;
 use crate::release::Release;
+use crate::render::Backend;
 use crate::render::Render;
+use crate::resize::Resize;
+use crate::rng;
+use crate::rng::GameRng;
+use crate::scroll::Scroll;
+use crate::text::Text;
 use crate::update::Update;
+use crate::widgets::Label;
+use crate::widgets::Panel;
+use crate::widgets::Widget;
+
+
+// sizes are at the reference window size, see layout::Screen
+/// space between the edge of the window and the planning screen's contents
+const MARGIN: f64 = 160.0;
+const BUDGET_FONT_SIZE: u32 = 32;
+const DETAILS_FONT_SIZE: u32 = 16;
+const LINE_SPACING: f64 = 8.0;
 
 
-pub enum MissionState {
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MissionPhase {
     Planning {
         budget: f32,
     },
     Execution {
-
+        /// simulated seconds since execution began
+        elapsed: f64,
     },
 }
 
+
+/// everything about a mission. saves::v1 has what's saved of it
+pub struct MissionState {
+    pub difficulty: MissionDifficulty,
+    /// the seed rng started from. two missions with the same seed and difficulty play out the same
+    pub seed: u64,
+    /// every random decision in the mission is drawn from this, in a deterministic order. nothing
+    /// is random yet
+    pub rng: GameRng,
+    pub phase: MissionPhase,
+    /// name of the save slot the mission was last saved to or loaded from, which saving again
+    /// overwrites. None until it's saved
+    pub slot: Option<String>,
+    /// what the planning phase shows
+    planning: Panel,
+}
+
+impl MissionState {
+    pub fn new(difficulty: MissionDifficulty, seed: u64) -> MissionState {
+        let budget = match difficulty {
+            MissionDifficulty::Easy => 1000.0,
+            MissionDifficulty::Medium => 750.0,
+            MissionDifficulty::Hard => 500.0,
+        };
+        MissionState::resume(difficulty, seed, rng::seeded(seed), MissionPhase::Planning { budget })
+    }
+
+    /// a mission carried on from where it was saved
+    pub fn resume(difficulty: MissionDifficulty, seed: u64, rng: GameRng, phase: MissionPhase) -> MissionState {
+        let mut mission = MissionState {
+            difficulty,
+            seed,
+            rng,
+            phase,
+            slot: None,
+            planning: MissionState::planning_panel(),
+        };
+        mission.update_labels();
+        mission
+    }
+
+    /// the planning screen's widgets, before the labels are filled in
+    fn planning_panel() -> Panel {
+        Panel::new()
+            .child(Widget::Label(Label::new("budget", "", BUDGET_FONT_SIZE)))
+            .child(Widget::Label(Label::new("details", "", DETAILS_FONT_SIZE)))
+    }
+
+    /// how far the mission has got, for listing saves
+    pub fn progress(&self) -> String {
+        match self.phase {
+            MissionPhase::Planning { budget } => format!("planning, ${} left", budget),
+            MissionPhase::Execution { elapsed } => format!("executing, {:.0}s in", elapsed),
+        }
+    }
+
+    /// show the current state of the mission on the planning screen. has to be called whenever the
+    /// budget changes
+    pub fn update_labels(&mut self) {
+        let budget = match self.phase {
+            MissionPhase::Planning { budget } => budget,
+            MissionPhase::Execution { .. } => return,
+        };
+        let details = format!("{:?} mission, seed {}", self.difficulty, self.seed);
+        for (id, text) in [("budget", format!("Remaining Budget: ${:?} (millions)", budget)), ("details", details)] {
+            if let Some(Widget::Label(label)) = self.planning.find_mut(id) {
+                label.text = text;
+            }
+        }
+    }
+
+    /// the widgets for the current phase, if it has any
+    fn panel(&mut self) -> Option<&mut Panel> {
+        match self.phase {
+            MissionPhase::Planning { .. } => Some(&mut self.planning),
+            MissionPhase::Execution { .. } => None,
+        }
+    }
+}
+
+impl Resize for MissionState {
+    /// the budget and details sit in the bottom left, one above the other
+    fn resize(&mut self, window_size: [f64; 2]) {
+        let screen = Screen::new(window_size);
+        let area = layout::inset(screen.rect, screen.px(MARGIN));
+        let lines = [("budget", BUDGET_FONT_SIZE), ("details", DETAILS_FONT_SIZE)];
+        let height: f64 = lines.iter().map(|(_, size)| screen.px(*size as f64 + LINE_SPACING)).sum();
+        let mut stack = Stack::new(layout::anchor(area, Anchor::BottomLeft, [area[2], height]), Direction::Down, 0.0);
+        for (id, size) in lines {
+            let rect = stack.next(screen.px(size as f64 + LINE_SPACING));
+            if let Some(Widget::Label(label)) = self.planning.find_mut(id) {
+                label.rect = rect;
+                label.size = screen.font(size);
+            }
+        }
+    }
+}
+
 impl Hover for MissionState {
-    fn hover(&mut self, window_size: [f64; 2], cursor: [f64; 2]) -> Option<AppEvent> {
-        None
+    fn hover(&mut self, window_size: [f64; 2], cursor: [f64; 2], bus: &mut EventBus) {
+        if let Some(panel) = self.panel() {
+            panel.hover(window_size, cursor, bus);
+        }
+    }
+}
+
+impl Axis for MissionState {
+    fn axis(&mut self, _args: ControllerAxisArgs, _bus: &mut EventBus) {
     }
 }
 
 impl Press for MissionState {
-    fn press(&mut self, button: Button) -> Option<AppEvent> {
-        println!("mission registered press of {:?}", button);
-        None
+    fn press(&mut self, action: Action, bus: &mut EventBus) {
+        debug!("mission registered press of {:?}", action);
+        if let Some(panel) = self.panel() {
+            panel.press(action, bus);
+        }
     }
 }
 
 impl Release for MissionState {
-    fn release(&mut self, button: Button) -> Option<AppEvent> {
-        if let Button::Keyboard(Key::Escape) = button {
-            Some(AppEvent::OpenMenu)
-        } else {
-            None
+    fn release(&mut self, action: Action, bus: &mut EventBus) {
+        match action {
+            Action::Pause => bus.emit(AppEvent::OpenMenu),
+            Action::TimeWarpUp => bus.emit(AppEvent::StepTimeWarp(true)),
+            Action::TimeWarpDown => bus.emit(AppEvent::StepTimeWarp(false)),
+            _ => if let Some(panel) = self.panel() {
+                panel.release(action, bus);
+            },
+        }
+    }
+}
+
+impl Text for MissionState {
+    fn text(&mut self, text: &str, bus: &mut EventBus) {
+        if let Some(panel) = self.panel() {
+            panel.text(text, bus);
+        }
+    }
+}
+
+impl Scroll for MissionState {
+    fn scroll(&mut self, delta: [f64; 2], bus: &mut EventBus) {
+        if let Some(panel) = self.panel() {
+            panel.scroll(delta, bus);
         }
     }
 }
 
 impl Render for MissionState {
-    fn render(
+    fn render<G: Backend>(
         &self,
         c: &Context,
-        gl: &mut GlGraphics
+        g: &mut G
     ) {
         let [w, h] = c.get_view_size();
-        clear(*colors::BLACK, gl);
-        let mut font = fonts::FONT.lock().unwrap();
+        clear(colors::palette().black, g);
 
-        match self {
-            MissionState::Planning { budget } => {
-                text(*colors::RED, 32, &format!("Remaining Budget: ${:?} (millions)", budget), &mut *font, c.transform.trans(w * 0.1, h - w * 0.1), gl).unwrap();
+        match self.phase {
+            MissionPhase::Planning { .. } => self.planning.render(c, g),
+            MissionPhase::Execution { .. } => {
+                g.draw_text(colors::palette().red, 32, "under construction", Font::Regular, c.transform.trans(w * 0.1, h - w * 0.1));
             },
-            _ => text(*colors::RED, 32, "under construction", &mut *font, c.transform.trans(w * 0.1, h - w * 0.1), gl).unwrap(),
         }
-
     }
 }
 
 impl Update for MissionState {
-    fn update(&mut self) -> Option<AppEvent> {
-        None
+    fn update(&mut self, dt: f64, _bus: &mut EventBus) {
+        if let MissionPhase::Execution { elapsed } = &mut self.phase {
+            *elapsed += dt;
+        }
     }
 }
\ No newline at end of file
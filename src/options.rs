@@ -0,0 +1,294 @@
+use graphics::clear;
+use graphics::Context;
+use piston::input::Button as InputButton;
+
+use crate::actions::Action;
+use crate::actions::Bindings;
+use crate::app::AppEvent;
+use crate::colors;
+use crate::config::Config;
+use crate::config::MAX_UI_SCALE;
+use crate::config::MIN_UI_SCALE;
+use crate::config::Setting;
+use crate::events::EventBus;
+use crate::gamepad;
+use crate::hover::Hover;
+use crate::layout;
+use crate::layout::Direction;
+use crate::layout::Screen;
+use crate::layout::Stack;
+use crate::press::Press;
+use crate::release::Release;
+use crate::render::Backend;
+use crate::render::Render;
+use crate::resize::Resize;
+use crate::scroll::Scroll;
+use crate::text::Text;
+use crate::widgets;
+use crate::widgets::Button;
+use crate::widgets::Dropdown;
+use crate::widgets::Label;
+use crate::widgets::Panel;
+use crate::widgets::Slider;
+use crate::widgets::Toggle;
+use crate::widgets::Widget;
+use crate::widgets::WidgetEvent;
+
+
+// sizes are at the reference window size, see layout::Screen
+const MARGIN: f64 = 120.0;
+const TITLE_FONT_SIZE: u32 = 64;
+const HEADING_FONT_SIZE: u32 = 32;
+const ROW_SPACING: f64 = 8.0;
+/// the bindings are packed closer together so they all fit
+const BINDING_SPACING: f64 = 4.0;
+/// widest a column gets on wide screens
+const MAX_COLUMN_WIDTH: f64 = 720.0;
+/// the bindings take up nearly the whole height at the reference size, so the screen is never
+/// scaled up past fitting this
+const CONTENT_SIZE: [f64; 2] = [1920.0, 1080.0];
+
+/// window sizes offered when windowed, besides whatever is configured already
+const RESOLUTIONS: [(u32, u32); 5] = [(1280, 720), (1600, 900), (1920, 1080), (2560, 1440), (3840, 2160)];
+
+/// every action which can be rebound, with the id of the button that rebinds it
+const BINDINGS: [(Action, &str); 15] = [
+    (Action::Select, "bind_select"),
+    (Action::Confirm, "bind_confirm"),
+    (Action::Back, "bind_back"),
+    (Action::Up, "bind_up"),
+    (Action::Down, "bind_down"),
+    (Action::Left, "bind_left"),
+    (Action::Right, "bind_right"),
+    (Action::Next, "bind_next"),
+    (Action::Erase, "bind_erase"),
+    (Action::Pause, "bind_pause"),
+    (Action::TimeWarpUp, "bind_time_warp_up"),
+    (Action::TimeWarpDown, "bind_time_warp_down"),
+    (Action::ToggleOverlay, "bind_toggle_overlay"),
+    (Action::ToggleConsole, "bind_toggle_console"),
+    (Action::Screenshot, "bind_screenshot"),
+];
+
+/// the settings column, top to bottom. the bindings column is BINDINGS under a heading
+const SETTINGS: [&str; 9] = [
+    "master_volume",
+    "music_volume",
+    "sfx_volume",
+    "fullscreen",
+    "resolution",
+    "ui_scale",
+    "colorblind",
+    "status",
+    "close",
+];
+
+/// the status until a keybinding is changed
+const BINDINGS_HINT: &str = "select a keybinding, then press a button for it";
+
+
+/// changes settings and keybindings. each change is reported with AppEvent::ChangeSetting or
+/// AppEvent::Rebind as soon as it's made, and the app applies it. settings are saved with
+/// AppEvent::SaveSettings once the button making the change comes up, so dragging a slider saves once
+pub struct OptionsState {
+    panel: Panel,
+    /// the window sizes in the resolution dropdown, in order
+    resolutions: Vec<(u32, u32)>,
+}
+
+impl OptionsState {
+    pub fn new(config: &Config, bindings: &Bindings) -> OptionsState {
+        let mut resolutions = RESOLUTIONS.to_vec();
+        if !resolutions.contains(&config.resolution) {
+            resolutions.push(config.resolution);
+        }
+        let selected = resolutions.iter().position(|resolution| *resolution == config.resolution).unwrap_or(0);
+        let percent = |value: f64| format!("{:.0}%", value * 100.0);
+        let mut panel = Panel::new()
+            .child(Widget::Label(Label::new("title", "options", TITLE_FONT_SIZE)))
+            .child(Widget::Slider(Slider::new("master_volume", "master volume", config.master_volume as f64, 0.0, 1.0, 0.05).format(percent)))
+            .child(Widget::Slider(Slider::new("music_volume", "music volume", config.music_volume as f64, 0.0, 1.0, 0.05).format(percent)))
+            .child(Widget::Slider(Slider::new("sfx_volume", "effects volume", config.sfx_volume as f64, 0.0, 1.0, 0.05).format(percent)))
+            .child(Widget::Toggle(Toggle::new("fullscreen", "fullscreen", config.fullscreen)))
+            .child(Widget::Dropdown(Dropdown::new("resolution", resolutions.iter().map(|(w, h)| format!("{}x{}", w, h)).collect(), selected)))
+            .child(Widget::Slider(Slider::new("ui_scale", "ui scale", config.ui_scale, MIN_UI_SCALE, MAX_UI_SCALE, 0.1).format(|value| format!("{:.1}x", value))))
+            .child(Widget::Toggle(Toggle::new("colorblind", "colorblind palette", config.colorblind)))
+            .child(Widget::Label(Label::new("status", BINDINGS_HINT, widgets::font_size(widgets::HEIGHT))))
+            .child(Widget::Button(Button::new("close", "back")))
+            .child(Widget::Label(Label::new("bindings", "keybindings", HEADING_FONT_SIZE)));
+        for (_, id) in BINDINGS.iter() {
+            panel = panel.child(Widget::Button(Button::new(id, "")));
+        }
+        let mut options = OptionsState { panel, resolutions };
+        options.show_bindings(bindings);
+        options
+    }
+
+    /// update the bindings buttons to show what each action is bound to
+    pub fn show_bindings(&mut self, bindings: &Bindings) {
+        for (action, id) in BINDINGS.iter() {
+            if let Some(Widget::Button(button)) = self.panel.find_mut(id) {
+                let buttons: Vec<String> = bindings.buttons(*action).iter().map(button_name).collect();
+                button.text = format!("{}: {}", action_name(*action), buttons.join(", "));
+            }
+        }
+    }
+
+    /// show a message under the settings, e.g. why a binding was refused
+    pub fn set_status(&mut self, status: &str) {
+        if let Some(Widget::Label(label)) = self.panel.find_mut("status") {
+            label.text = status.to_string();
+        }
+    }
+
+    /// id of the widget under the cursor, if any
+    pub fn hovered(&self) -> Option<&str> {
+        self.panel.hovered()
+    }
+
+    /// put the status back after the player backs out of a rebind
+    pub fn cancel_rebind(&mut self) {
+        self.set_status(BINDINGS_HINT);
+    }
+
+    /// turn whatever the widgets reported into settings changes
+    fn handle_widget_events(&mut self, bus: &mut EventBus) {
+        for event in self.panel.take_events() {
+            let setting = match event {
+                WidgetEvent::Changed("master_volume", volume) => Setting::MasterVolume(volume as f32),
+                WidgetEvent::Changed("music_volume", volume) => Setting::MusicVolume(volume as f32),
+                WidgetEvent::Changed("sfx_volume", volume) => Setting::SfxVolume(volume as f32),
+                WidgetEvent::Toggled("fullscreen", fullscreen) => Setting::Fullscreen(fullscreen),
+                WidgetEvent::Selected("resolution", i) => {
+                    let (w, h) = self.resolutions[i];
+                    Setting::Resolution(w, h)
+                },
+                WidgetEvent::Changed("ui_scale", scale) => Setting::UiScale(scale),
+                WidgetEvent::Toggled("colorblind", colorblind) => Setting::Colorblind(colorblind),
+                WidgetEvent::Clicked("close") => {
+                    bus.emit(AppEvent::CloseOptions);
+                    continue
+                },
+                WidgetEvent::Clicked(id) => {
+                    if let Some((action, _)) = BINDINGS.iter().find(|(_, binding)| *binding == id) {
+                        self.set_status(&format!("press a button for {}, or back to cancel", action_name(*action)));
+                        bus.emit(AppEvent::Rebind(*action));
+                    }
+                    continue
+                },
+                _ => continue,
+            };
+            bus.emit(AppEvent::ChangeSetting(setting));
+        }
+    }
+}
+
+/// e.g. "time warp up" for Action::TimeWarpUp
+fn action_name(action: Action) -> String {
+    let mut name = String::new();
+    for ch in format!("{:?}", action).chars() {
+        if ch.is_uppercase() && !name.is_empty() {
+            name.push(' ');
+        }
+        name.extend(ch.to_lowercase());
+    }
+    name
+}
+
+fn button_name(button: &InputButton) -> String {
+    match button {
+        InputButton::Keyboard(key) => format!("{:?}", key),
+        InputButton::Mouse(mouse) => format!("Mouse {:?}", mouse),
+        InputButton::Controller(controller) => gamepad::button_name(controller.button),
+        _ => format!("{:?}", button),
+    }
+}
+
+impl Resize for OptionsState {
+    /// settings down the left, bindings down the right
+    fn resize(&mut self, window_size: [f64; 2]) {
+        let screen = Screen::new(window_size).fit(CONTENT_SIZE);
+        let [x, y, w, h] = layout::inset(screen.rect, screen.px(MARGIN));
+        let row = screen.px(widgets::HEIGHT);
+        let column = (w / 2.0 - screen.px(MARGIN) / 2.0).min(screen.px(MAX_COLUMN_WIDTH));
+
+        let title_height = screen.px(TITLE_FONT_SIZE as f64);
+        if let Some(Widget::Label(title)) = self.panel.find_mut("title") {
+            title.rect = [x, y, column, title_height];
+            title.size = screen.font(TITLE_FONT_SIZE);
+        }
+        let below_title = [x, y + title_height + screen.px(ROW_SPACING * 2.0), w, h - title_height];
+
+        let mut settings = Stack::new([below_title[0], below_title[1], column, below_title[3]], Direction::Down, screen.px(ROW_SPACING));
+        for id in SETTINGS.iter() {
+            let rect = settings.next(row);
+            if let Some(widget) = self.panel.find_mut(id) {
+                widget.set_rect(rect);
+                if let Widget::Label(label) = widget {
+                    label.size = widgets::font_size(row);
+                }
+            }
+        }
+
+        let right = [x + w - column, below_title[1], column, below_title[3]];
+        let mut bindings = Stack::new(right, Direction::Down, screen.px(BINDING_SPACING));
+        let heading = bindings.next(screen.px(HEADING_FONT_SIZE as f64 + ROW_SPACING));
+        if let Some(Widget::Label(label)) = self.panel.find_mut("bindings") {
+            label.rect = heading;
+            label.size = screen.font(HEADING_FONT_SIZE);
+        }
+        for (_, id) in BINDINGS.iter() {
+            let rect = bindings.next(row);
+            if let Some(widget) = self.panel.find_mut(id) {
+                widget.set_rect(rect);
+            }
+        }
+    }
+}
+
+impl Render for OptionsState {
+    fn render<G: Backend>(&self, c: &Context, g: &mut G) {
+        clear(colors::palette().black, g);
+        self.panel.render(c, g);
+    }
+}
+
+impl Hover for OptionsState {
+    fn hover(&mut self, window_size: [f64; 2], cursor: [f64; 2], bus: &mut EventBus) {
+        // sliders move as they're dragged
+        self.panel.hover(window_size, cursor, bus);
+        self.handle_widget_events(bus);
+    }
+}
+
+impl Press for OptionsState {
+    fn press(&mut self, action: Action, bus: &mut EventBus) {
+        self.panel.press(action, bus);
+        self.handle_widget_events(bus);
+    }
+}
+
+impl Release for OptionsState {
+    fn release(&mut self, action: Action, bus: &mut EventBus) {
+        // Back closes an open dropdown before it closes the options
+        let captured = self.panel.capturing().is_some();
+        self.panel.release(action, bus);
+        self.handle_widget_events(bus);
+        bus.emit(AppEvent::SaveSettings);
+        if action == Action::Back && !captured {
+            bus.emit(AppEvent::CloseOptions);
+        }
+    }
+}
+
+impl Text for OptionsState {
+    fn text(&mut self, text: &str, bus: &mut EventBus) {
+        self.panel.text(text, bus);
+    }
+}
+
+impl Scroll for OptionsState {
+    fn scroll(&mut self, delta: [f64; 2], bus: &mut EventBus) {
+        self.panel.scroll(delta, bus);
+    }
+}
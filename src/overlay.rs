@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+use std::collections::VecDeque;
+use std::time::Duration;
+use std::time::Instant;
+
+use fps_counter::FPSCounter;
+use graphics::Context;
+use graphics::rectangle;
+use graphics::Transformed;
+
+use crate::app::AppEvent;
+use crate::colors;
+use crate::fonts::Font;
+use crate::render::Backend;
+use crate::scene::SceneStack;
+
+
+const FONT_SIZE: u32 = 14;
+const LINE_HEIGHT: f64 = 20.0;
+const PADDING: f64 = 10.0;
+const WIDTH: f64 = 360.0;
+/// number of frames shown in the frame time graph, one pixel wide each
+const GRAPH_FRAMES: usize = 240;
+const GRAPH_HEIGHT: f64 = 60.0;
+/// frame time at the top of the graph
+const GRAPH_MAX_MS: f64 = 50.0;
+/// frame time the graph marks with a line, a 60Hz frame
+const TARGET_MS: f64 = 1000.0 / 60.0;
+const BACKGROUND: [f32; 4] = [0.0, 0.0, 0.0, 0.75];
+const GRAPH_COLOR: [f32; 4] = [0.3, 0.8, 0.3, 1.0];
+const SLOW_FRAME_COLOR: [f32; 4] = [0.9, 0.2, 0.2, 1.0];
+const TARGET_LINE_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.4];
+
+
+/// frame and simulation timing plus what the app is doing, drawn over every scene while visible.
+/// measured in wall clock time, so it has no effect on the simulation
+pub struct DebugOverlay {
+    pub visible: bool,
+    fps_counter: FPSCounter,
+    fps: usize,
+    last_frame: Option<Instant>,
+    /// milliseconds between recent frames, oldest first
+    frame_times: VecDeque<f64>,
+    /// when each simulation step in the last second happened
+    steps: VecDeque<Instant>,
+    /// when each event in the last second was handled, and which kind it was
+    events: VecDeque<(Instant, &'static str)>,
+}
+
+impl DebugOverlay {
+    pub fn new() -> DebugOverlay {
+        DebugOverlay {
+            visible: false,
+            fps_counter: FPSCounter::new(),
+            fps: 0,
+            last_frame: None,
+            frame_times: VecDeque::with_capacity(GRAPH_FRAMES),
+            steps: VecDeque::new(),
+            events: VecDeque::new(),
+        }
+    }
+
+    /// call once per rendered frame
+    pub fn frame(&mut self) {
+        let now = Instant::now();
+        self.fps = self.fps_counter.tick();
+        if let Some(last_frame) = self.last_frame {
+            if self.frame_times.len() == GRAPH_FRAMES {
+                self.frame_times.pop_front();
+            }
+            self.frame_times.push_back((now - last_frame).as_secs_f64() * 1000.0);
+        }
+        self.last_frame = Some(now);
+    }
+
+    /// call once per simulation step
+    pub fn step(&mut self) {
+        let now = Instant::now();
+        self.steps.push_back(now);
+        forget_before(&mut self.steps, now, |t| *t);
+    }
+
+    /// call for every event AppState handles
+    pub fn event(&mut self, event: &AppEvent) {
+        let now = Instant::now();
+        self.events.push_back((now, event.name()));
+        forget_before(&mut self.events, now, |(t, _)| *t);
+    }
+
+    pub fn render<G: Backend>(&self, c: &Context, g: &mut G, scenes: &SceneStack) {
+        let now = Instant::now();
+        let a_second_ago = now.checked_sub(Duration::from_secs(1)).unwrap_or(now);
+        let ups = self.steps.iter().filter(|t| **t >= a_second_ago).count();
+        let mut event_counts = BTreeMap::new();
+        for (_, name) in self.events.iter().filter(|(t, _)| *t >= a_second_ago) {
+            *event_counts.entry(*name).or_insert(0) += 1;
+        }
+        let worst_frame = self.frame_times.iter().cloned().fold(0.0, f64::max);
+
+        let mut lines = vec![
+            format!("fps: {}  worst frame: {:.1}ms", self.fps, worst_frame),
+            format!("simulation steps/s: {}", ups),
+            format!("scenes: {}", scenes.names().join(" > ")),
+            format!("hovered: {}", scenes.hovered().unwrap_or("nothing")),
+            "events in the last second:".to_string(),
+        ];
+        if event_counts.is_empty() {
+            lines.push("    none".to_string());
+        }
+        for (name, count) in event_counts {
+            lines.push(format!("    {}: {}", name, count));
+        }
+
+        let [w, _h] = c.get_view_size();
+        let x = w - WIDTH - PADDING;
+        let text_height = lines.len() as f64 * LINE_HEIGHT;
+        let height = text_height + GRAPH_HEIGHT + PADDING * 3.0;
+        rectangle(BACKGROUND, [x, PADDING, WIDTH, height], c.transform, g);
+        for (i, line) in lines.iter().enumerate() {
+            let y = PADDING * 2.0 + LINE_HEIGHT * (i as f64 + 0.5);
+            g.draw_text(colors::palette().mars, FONT_SIZE, line, Font::Regular, c.transform.trans(x + PADDING, y));
+        }
+
+        // frame time graph, newest frame on the right
+        let bottom = PADDING * 2.0 + text_height + GRAPH_HEIGHT;
+        let scale = GRAPH_HEIGHT / GRAPH_MAX_MS;
+        let right = x + PADDING + GRAPH_FRAMES as f64;
+        for (i, ms) in self.frame_times.iter().rev().enumerate() {
+            let bar = ms.min(GRAPH_MAX_MS) * scale;
+            let color = if *ms > TARGET_MS { SLOW_FRAME_COLOR } else { GRAPH_COLOR };
+            rectangle(color, [right - i as f64 - 1.0, bottom - bar, 1.0, bar], c.transform, g);
+        }
+        let target = bottom - TARGET_MS * scale;
+        rectangle(TARGET_LINE_COLOR, [x + PADDING, target, GRAPH_FRAMES as f64, 1.0], c.transform, g);
+    }
+}
+
+/// drop entries more than a second older than now
+fn forget_before<T, F: Fn(&T) -> Instant>(entries: &mut VecDeque<T>, now: Instant, time: F) {
+    while entries.front().is_some_and(|entry| now.duration_since(time(entry)) > Duration::from_secs(1)) {
+        entries.pop_front();
+    }
+}
@@ -1,9 +1,8 @@
-use piston::input::Button;
+use crate::actions::Action;
+use crate::events::EventBus;
 
-use crate::app::AppEvent;
 
-
-/// trait for types which can respond to keyboard and mouse button down events
+/// trait for types which can respond to the buttons bound to an action going down
 pub trait Press {
-    fn press(&mut self, button: Button) -> Option<AppEvent>;
+    fn press(&mut self, action: Action, bus: &mut EventBus);
 }
\ No newline at end of file
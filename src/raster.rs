@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use graphics::DrawState;
+use graphics::Graphics;
+use graphics::ImageSize;
+use graphics::Transformed;
+use graphics::math::Matrix2d;
+use graphics::types::Color;
+use graphics::types::FontSize;
+use image::Rgba;
+use image::RgbaImage;
+use rusttype::Scale;
+use rusttype::point;
+
+use crate::assets;
+use crate::fonts::Font;
+use crate::images::Image;
+use crate::render::Backend;
+
+
+/// an image as a texture for RasterGraphics
+pub struct RasterTexture {
+    image: RgbaImage,
+}
+
+impl ImageSize for RasterTexture {
+    fn get_size(&self) -> (u32, u32) {
+        self.image.dimensions()
+    }
+}
+
+
+/// draws on the CPU into an image, so frames can be saved without a window or GPU. fills triangles
+/// at pixel centers with no antialiasing, which is close enough to compare frames and read text
+pub struct RasterGraphics {
+    pub buffer: RgbaImage,
+    /// None for images which failed to load
+    textures: HashMap<Image, Option<RasterTexture>>,
+}
+
+impl RasterGraphics {
+    pub fn new(width: u32, height: u32) -> RasterGraphics {
+        RasterGraphics { buffer: RgbaImage::new(width, height), textures: HashMap::new() }
+    }
+}
+
+impl Graphics for RasterGraphics {
+    type Texture = RasterTexture;
+
+    fn clear_color(&mut self, color: Color) {
+        let pixel = Rgba { data: [to_byte(color[0]), to_byte(color[1]), to_byte(color[2]), to_byte(color[3])] };
+        for p in self.buffer.pixels_mut() {
+            *p = pixel;
+        }
+    }
+
+    fn clear_stencil(&mut self, _value: u8) {}
+
+    fn tri_list<F>(&mut self, _draw_state: &DrawState, color: &[f32; 4], mut f: F)
+        where F: FnMut(&mut dyn FnMut(&[[f32; 2]])) {
+        let buffer = &mut self.buffer;
+        f(&mut |vertices| {
+            for triangle in vertices.chunks(3) {
+                fill_triangle(buffer, triangle, None, *color);
+            }
+        });
+    }
+
+    fn tri_list_uv<F>(&mut self, _draw_state: &DrawState, color: &[f32; 4], texture: &RasterTexture, mut f: F)
+        where F: FnMut(&mut dyn FnMut(&[[f32; 2]], &[[f32; 2]])) {
+        let buffer = &mut self.buffer;
+        f(&mut |vertices, uvs| {
+            for (triangle, uv) in vertices.chunks(3).zip(uvs.chunks(3)) {
+                fill_triangle(buffer, triangle, Some((uv, &texture.image)), *color);
+            }
+        });
+    }
+}
+
+impl Backend for RasterGraphics {
+    fn draw_text(&mut self, color: Color, size: FontSize, text: &str, font: Font, transform: Matrix2d) {
+        let assets = assets::get();
+        // GlyphCache converts points to pixels the same way
+        let scale = Scale::uniform(((size as f32) * 1.333).round());
+        // each glyph is drawn as a textured rectangle like GlyphCache does, so scaled text stays solid
+        for glyph in assets.font(font).layout(text, scale, point(0.0, 0.0)) {
+            let bounds = match glyph.pixel_bounding_box() {
+                Some(bounds) => bounds,
+                None => continue,
+            };
+            let mut image = RgbaImage::new(bounds.width() as u32, bounds.height() as u32);
+            glyph.draw(|x, y, coverage| image.put_pixel(x, y, Rgba { data: [255, 255, 255, to_byte(coverage)] }));
+            let texture = RasterTexture { image };
+            let transform = transform.trans(bounds.min.x as f64, bounds.min.y as f64);
+            graphics::Image::new_color(color).draw(&texture, &DrawState::default(), transform, self);
+        }
+    }
+
+    fn draw_image(&mut self, image: Image, transform: Matrix2d) {
+        let texture = match self.textures.remove(&image) {
+            Some(texture) => texture,
+            None => assets::get().image(image).map(|image| RasterTexture { image: image.clone() }),
+        };
+        if let Some(texture) = &texture {
+            graphics::image(texture, transform, self);
+        }
+        self.textures.insert(image, texture);
+    }
+}
+
+
+/// normalized device coordinates to pixel coordinates
+fn to_pixel([x, y]: [f64; 2], w: f64, h: f64) -> [f64; 2] {
+    [(x + 1.0) * 0.5 * w, (1.0 - y) * 0.5 * h]
+}
+
+fn to_byte(channel: f32) -> u8 {
+    (channel.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// color over the pixel, with color's alpha
+fn blend(pixel: &mut Rgba<u8>, color: Color) {
+    let alpha = color[3].clamp(0.0, 1.0);
+    for (channel, over) in pixel.data.iter_mut().zip(color.iter()).take(3) {
+        let under = *channel as f32 / 255.0;
+        *channel = to_byte(over * alpha + under * (1.0 - alpha));
+    }
+    let under = pixel.data[3] as f32 / 255.0;
+    pixel.data[3] = to_byte(alpha + under * (1.0 - alpha));
+}
+
+/// twice the signed area of the triangle a, b, p
+fn edge(a: [f64; 2], b: [f64; 2], p: [f64; 2]) -> f64 {
+    (b[0] - a[0]) * (p[1] - a[1]) - (b[1] - a[1]) * (p[0] - a[0])
+}
+
+/// fill every pixel whose center is inside the triangle, given in normalized device coordinates.
+/// textured triangles multiply color by the nearest texel
+fn fill_triangle(buffer: &mut RgbaImage, triangle: &[[f32; 2]], texture: Option<(&[[f32; 2]], &RgbaImage)>, color: Color) {
+    if triangle.len() < 3 {
+        return
+    }
+    let (w, h) = (buffer.width() as f64, buffer.height() as f64);
+    let p: Vec<[f64; 2]> = triangle.iter().map(|v| to_pixel([v[0] as f64, v[1] as f64], w, h)).collect();
+    let area = edge(p[0], p[1], p[2]);
+    if area == 0.0 {
+        return
+    }
+    let min_x = p.iter().map(|v| v[0]).fold(f64::INFINITY, f64::min).max(0.0).floor() as u32;
+    let max_x = p.iter().map(|v| v[0]).fold(f64::NEG_INFINITY, f64::max).min(w).ceil() as u32;
+    let min_y = p.iter().map(|v| v[1]).fold(f64::INFINITY, f64::min).max(0.0).floor() as u32;
+    let max_y = p.iter().map(|v| v[1]).fold(f64::NEG_INFINITY, f64::max).min(h).ceil() as u32;
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let center = [x as f64 + 0.5, y as f64 + 0.5];
+            let b0 = edge(p[1], p[2], center) / area;
+            let b1 = edge(p[2], p[0], center) / area;
+            let b2 = edge(p[0], p[1], center) / area;
+            if b0 < 0.0 || b1 < 0.0 || b2 < 0.0 {
+                continue
+            }
+            let mut color = color;
+            if let Some((uv, image)) = texture {
+                let u = b0 * uv[0][0] as f64 + b1 * uv[1][0] as f64 + b2 * uv[2][0] as f64;
+                let v = b0 * uv[0][1] as f64 + b1 * uv[1][1] as f64 + b2 * uv[2][1] as f64;
+                let tx = ((u * image.width() as f64) as u32).min(image.width() - 1);
+                let ty = ((v * image.height() as f64) as u32).min(image.height() - 1);
+                let texel = image.get_pixel(tx, ty);
+                for (channel, texel) in color.iter_mut().zip(texel.data.iter()) {
+                    *channel *= *texel as f32 / 255.0;
+                }
+            }
+            blend(buffer.get_pixel_mut(x, y), color);
+        }
+    }
+}
@@ -1,9 +1,8 @@
-use piston::input::Button;
+use crate::actions::Action;
+use crate::events::EventBus;
 
-use crate::app::AppEvent;
 
-
-/// trait for types which can respond to keyboard and mouse button up events
+/// trait for types which can respond to the buttons bound to an action going up
 pub trait Release {
-    fn release(&mut self, button: Button) -> Option<AppEvent>;
+    fn release(&mut self, action: Action, bus: &mut EventBus);
 }
\ No newline at end of file
@@ -1,16 +1,107 @@
 use std::collections::HashMap;
 
 use graphics::Context;
+use graphics::DrawState;
+use graphics::Graphics;
+use graphics::math::Matrix2d;
 use graphics::types::Color;
+use graphics::types::FontSize;
 use opengl_graphics::GlGraphics;
 use opengl_graphics::GlyphCache;
 use opengl_graphics::Texture;
+use opengl_graphics::TextureSettings;
+
+use crate::assets;
+use crate::fonts::Font;
+use crate::images::Image;
+
+
+/// a graphics backend plus the text and image drawing the game needs from it. drawing text and
+/// images needs textures that belong to the backend, so each backend supplies its own
+pub trait Backend: Graphics {
+    fn draw_text(&mut self, color: Color, size: FontSize, text: &str, font: Font, transform: Matrix2d);
+    fn draw_image(&mut self, image: Image, transform: Matrix2d);
+}
+
+
+/// glyph caches and textures uploaded from the loaded assets, created the first time each is drawn.
+/// lives as long as the GL context
+#[derive(Default)]
+pub struct GlResources {
+    /// the Assets revision these were built from
+    revision: u64,
+    glyphs: HashMap<Font, GlyphCache<'static>>,
+    /// None for images which failed to load, so they're only looked up once
+    textures: HashMap<Image, Option<Texture>>,
+}
+
+impl GlResources {
+    pub fn new() -> GlResources {
+        GlResources::default()
+    }
+
+    /// throw away anything built from assets which have since been reloaded. call before each frame
+    pub fn sync(&mut self) {
+        let revision = assets::get().revision;
+        if revision != self.revision {
+            self.glyphs.clear();
+            self.textures.clear();
+            self.revision = revision;
+        }
+    }
+}
+
+/// GlGraphics drawing text and images from GlResources
+pub struct GlBackend<'a> {
+    pub gl: &'a mut GlGraphics,
+    pub resources: &'a mut GlResources,
+}
+
+impl<'a> Graphics for GlBackend<'a> {
+    type Texture = Texture;
+
+    fn clear_color(&mut self, color: Color) {
+        self.gl.clear_color(color);
+    }
+
+    fn clear_stencil(&mut self, value: u8) {
+        self.gl.clear_stencil(value);
+    }
+
+    fn tri_list<F>(&mut self, draw_state: &DrawState, color: &[f32; 4], f: F)
+        where F: FnMut(&mut dyn FnMut(&[[f32; 2]])) {
+        self.gl.tri_list(draw_state, color, f);
+    }
+
+    fn tri_list_uv<F>(&mut self, draw_state: &DrawState, color: &[f32; 4], texture: &Texture, f: F)
+        where F: FnMut(&mut dyn FnMut(&[[f32; 2]], &[[f32; 2]])) {
+        self.gl.tri_list_uv(draw_state, color, texture, f);
+    }
+}
+
+impl<'a> Backend for GlBackend<'a> {
+    fn draw_text(&mut self, color: Color, size: FontSize, text: &str, font: Font, transform: Matrix2d) {
+        let glyphs = self.resources.glyphs.entry(font).or_insert_with(|| {
+            GlyphCache::from_font(assets::get().font(font).clone(), (), TextureSettings::new())
+        });
+        graphics::text(color, size, text, glyphs, transform, self.gl).unwrap();
+    }
+
+    fn draw_image(&mut self, image: Image, transform: Matrix2d) {
+        let texture = self.resources.textures.entry(image).or_insert_with(|| {
+            assets::get().image(image).map(|image| Texture::from_image(image, &TextureSettings::new()))
+        });
+        if let Some(texture) = texture {
+            graphics::image(texture, transform, self.gl);
+        }
+    }
+}
 
 
 pub trait Render {
-    fn render(
+    fn render<G: Backend>(
         &self,
         c: &Context,
-        gl: &mut GlGraphics
+        g: &mut G
     );
-}
\ No newline at end of file
+}
@@ -0,0 +1,226 @@
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::LineWriter;
+use std::io::Write;
+use std::path::Path;
+
+use piston::input::Button;
+use piston::input::ControllerAxisArgs;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::actions::Bindings;
+use crate::app::AppEvent;
+use crate::app::MissionDifficulty;
+
+
+/// raw input as the app receives it, before it's mapped to actions. this is everything that can
+/// affect the game once it's running, so recording it along with a Header is enough to reproduce a
+/// session
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Input {
+    Cursor([f64; 2]),
+    Press(Button),
+    Release(Button),
+    Axis(ControllerAxisArgs),
+    Resize([f64; 2]),
+    /// mouse wheel movement
+    Scroll([f64; 2]),
+    /// text typed on the keyboard, only used by the console
+    Text(String),
+}
+
+
+/// a launch option which skips the main menu
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Launch {
+    /// --new-mission, with --seed if it was given
+    NewMission(MissionDifficulty, Option<u64>),
+    /// --load. the save's whole text is kept rather than its path, so a recording doesn't depend on
+    /// the file staying as it was
+    LoadMission {
+        slot: Option<String>,
+        save: String,
+    },
+}
+
+impl Launch {
+    pub fn event(&self) -> AppEvent {
+        match self {
+            Launch::NewMission(difficulty, seed) => AppEvent::NewMission(*difficulty, *seed),
+            Launch::LoadMission { slot, save } => AppEvent::RestoreMission(slot.clone(), save.clone()),
+        }
+    }
+}
+
+
+/// everything outside the inputs that a session depends on, written as the first line of a recording.
+/// the window size is recorded as input
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Header {
+    pub seed: u64,
+    pub launch: Option<Launch>,
+    /// buttons are recorded rather than actions, so they have to map to the same actions on replay
+    pub bindings: Bindings,
+    /// the config's ui_scale, which sizes the widgets clicks are tested against. recordings from
+    /// before it was kept were made at 1
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f64,
+}
+
+fn default_ui_scale() -> f64 {
+    1.0
+}
+
+
+/// writes inputs to a file as they happen. the first line is the Header, every line after is a RON
+/// `(tick, input)` pair. lines are flushed as they're written so a crash doesn't lose the recording
+pub struct Recorder {
+    file: LineWriter<File>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path, header: &Header) -> Result<Recorder, String> {
+        let file = File::create(path).map_err(|e| format!("failed to create {}: {}", path.display(), e))?;
+        let mut recorder = Recorder { file: LineWriter::new(file) };
+        let line = ron::ser::to_string(header).map_err(|e| e.to_string())?;
+        writeln!(recorder.file, "{}", line).map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+        Ok(recorder)
+    }
+
+    pub fn record(&mut self, tick: u64, input: &Input) -> Result<(), String> {
+        let line = ron::ser::to_string(&(tick, input)).map_err(|e| e.to_string())?;
+        writeln!(self.file, "{}", line).map_err(|e| e.to_string())
+    }
+}
+
+
+/// a recording being played back. inputs are handed out on the ticks they were recorded on
+pub struct Replay {
+    pub header: Header,
+    inputs: Vec<(u64, Input)>,
+    /// index of the next input to hand out
+    next: usize,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Replay, String> {
+        let file = File::open(path).map_err(|e| format!("failed to open {}: {}", path.display(), e))?;
+        let mut lines = BufReader::new(file).lines();
+        let read_error = |e| format!("failed to read {}: {}", path.display(), e);
+        let header = match lines.next() {
+            Some(line) => header(line.map_err(read_error)?.trim())
+                .map_err(|e| format!("bad header in {}: {}", path.display(), e))?,
+            None => return Err(format!("{} is empty", path.display())),
+        };
+        let mut inputs = Vec::new();
+        for (i, line) in lines.enumerate() {
+            let line = line.map_err(read_error)?;
+            let entry = ron::de::from_str(&line)
+                .map_err(|e| format!("bad input on line {} of {}: {}", i + 2, path.display(), e))?;
+            inputs.push(entry);
+        }
+        Ok(Replay { header, inputs, next: 0 })
+    }
+
+    /// the inputs recorded on this tick
+    pub fn due(&mut self, tick: u64) -> Vec<Input> {
+        let mut due = Vec::new();
+        while let Some((t, input)) = self.inputs.get(self.next) {
+            if *t > tick {
+                break
+            }
+            due.push(input.clone());
+            self.next += 1;
+        }
+        due
+    }
+
+    pub fn finished(&self) -> bool {
+        self.next >= self.inputs.len()
+    }
+}
+
+
+/// the header of a recording. older recordings start with just the seed, and were made with the
+/// default bindings from the main menu
+fn header(line: &str) -> Result<Header, String> {
+    match line.parse() {
+        Ok(seed) => Ok(Header { seed, launch: None, bindings: Bindings::defaults(), ui_scale: default_ui_scale() }),
+        Err(_) => ron::de::from_str(line).map_err(|e| e.to_string()),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::process;
+
+    use piston::input::Key;
+    use piston::input::MouseButton;
+
+    use crate::actions::Action;
+
+    use super::*;
+
+    fn replay(inputs: Vec<(u64, Input)>) -> Replay {
+        Replay { header: header("7").unwrap(), inputs, next: 0 }
+    }
+
+    #[test]
+    fn inputs_are_due_on_their_tick() {
+        let mut replay = replay(vec![
+            (0, Input::Resize([640.0, 360.0])),
+            (0, Input::Cursor([10.0, 20.0])),
+            (3, Input::Press(Button::Mouse(MouseButton::Left))),
+        ]);
+        assert_eq!(replay.due(0), [Input::Resize([640.0, 360.0]), Input::Cursor([10.0, 20.0])]);
+        assert_eq!(replay.due(1), []);
+        assert!(!replay.finished());
+        // a tick that's been skipped still hands out what was recorded on it
+        assert_eq!(replay.due(5), [Input::Press(Button::Mouse(MouseButton::Left))]);
+        assert!(replay.finished());
+        assert_eq!(replay.due(6), []);
+    }
+
+    #[test]
+    fn a_bare_seed_is_an_old_header() {
+        let header = header("18446744073709551615").unwrap();
+        assert_eq!(header.seed, u64::MAX);
+        assert_eq!(header.launch, None);
+        assert_eq!(header.bindings.conflicts(), Vec::new());
+        assert_eq!(header.bindings.buttons(Action::Pause), Bindings::defaults().buttons(Action::Pause));
+        assert_eq!(header.ui_scale, 1.0);
+    }
+
+    #[test]
+    fn headers_without_a_ui_scale_were_made_at_1() {
+        let header = header("(seed: 3, launch: None, bindings: {})").unwrap();
+        assert_eq!((header.seed, header.ui_scale), (3, 1.0));
+    }
+
+    #[test]
+    fn recordings_play_back() {
+        let path = env::temp_dir().join(format!("mars-recording-{}.txt", process::id()));
+        let mut bindings = Bindings::defaults();
+        bindings.rebind(Action::Pause, Button::Keyboard(Key::P)).unwrap();
+        let launch = Launch::LoadMission { slot: Some("base camp".to_string()), save: "mars save 1\n(\n)".to_string() };
+        let header = Header { seed: 42, launch: Some(launch), bindings, ui_scale: 0.7 };
+        let mut recorder = Recorder::create(&path, &header).unwrap();
+        recorder.record(0, &Input::Text("mission save".to_string())).unwrap();
+        recorder.record(2, &Input::Release(Button::Keyboard(Key::P))).unwrap();
+        drop(recorder);
+
+        let mut replay = Replay::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(replay.header.seed, 42);
+        assert_eq!(replay.header.launch, header.launch);
+        assert_eq!(replay.header.ui_scale, 0.7);
+        assert_eq!(replay.header.bindings.buttons(Action::Pause), header.bindings.buttons(Action::Pause));
+        assert_eq!(replay.due(2), [Input::Text("mission save".to_string()), Input::Release(Button::Keyboard(Key::P))]);
+        assert!(replay.finished());
+    }
+}
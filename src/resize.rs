@@ -0,0 +1,4 @@
+/// trait for types which lay themselves out to fit the window
+pub trait Resize {
+    fn resize(&mut self, window_size: [f64; 2]);
+}
@@ -0,0 +1,16 @@
+use rand::SeedableRng;
+use rand_pcg::Pcg32;
+
+
+/// the generator all game randomness comes from. PCG is small, fast and gives the same sequence for
+/// a seed on every platform and build
+pub type GameRng = Pcg32;
+
+/// a fresh seed for when the player hasn't asked for a specific one
+pub fn random_seed() -> u64 {
+    rand::random()
+}
+
+pub fn seeded(seed: u64) -> GameRng {
+    GameRng::seed_from_u64(seed)
+}
@@ -0,0 +1,344 @@
+use std::cmp::Reverse;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use ron::ser::PrettyConfig;
+use serde::de::DeserializeOwned;
+
+use crate::app::MissionDifficulty;
+use crate::mission::MissionState;
+
+mod v0;
+mod v1;
+
+
+/// the format saves are written in. bump it whenever anything saved changes shape: copy the newest
+/// module to one for the new version and change that, give the old one a migrate to the new one,
+/// and read it in upgrade. the old modules never change, so saves in their formats keep loading
+pub const VERSION: u32 = 1;
+/// starts the first line of every save, followed by the version. the rest of the file is the body,
+/// e.g. a v1::SaveFile
+const HEADER: &str = "mars save";
+/// longest name a save can have
+pub const MAX_NAME_LEN: usize = 32;
+const EXTENSION: &str = "ron";
+
+
+/// a save body in the format version it was written in
+enum Body {
+    V0(v0::SaveFile),
+    V1(v1::SaveFile),
+}
+
+
+/// what the load screen shows about a save which can be loaded
+#[derive(Clone, Debug)]
+pub struct Details {
+    pub saved_at: u64,
+    pub difficulty: MissionDifficulty,
+    /// how far the mission got, e.g. "planning, $750 left"
+    pub progress: String,
+}
+
+/// a file in the saves directory, as listed on the load screen, without loading the whole mission
+/// into a scene
+#[derive(Clone, Debug)]
+pub struct Slot {
+    /// the file name without its extension
+    pub name: String,
+    pub path: PathBuf,
+    /// what's in the save, or why it can't be loaded
+    pub details: Result<Details, String>,
+}
+
+impl Slot {
+    /// one line describing the save
+    pub fn summary(&self) -> String {
+        match &self.details {
+            Ok(details) => format!(
+                "{} - {} - {:?} - {}",
+                self.name,
+                format_time(details.saved_at),
+                details.difficulty,
+                details.progress,
+            ),
+            Err(_) => format!("{} - can't be loaded", self.name),
+        }
+    }
+}
+
+
+/// the file a save with this name is written to
+pub fn path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.{}", name, EXTENSION))
+}
+
+/// name with surrounding space trimmed, or why it can't name a save. names become file names, so
+/// they're limited to letters, digits, spaces, - and _
+pub fn check_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("a save needs a name".to_string())
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(format!("save names can't be longer than {} characters", MAX_NAME_LEN))
+    }
+    match name.chars().find(|ch| !(ch.is_alphanumeric() || *ch == ' ' || *ch == '-' || *ch == '_')) {
+        Some(ch) => Err(format!("save names can't contain {}", ch)),
+        None => Ok(name.to_string()),
+    }
+}
+
+/// write mission to the slot called name in dir, replacing whatever was there. the file is written
+/// next to the slot and moved over it, so a failed save never leaves half a file behind
+pub fn save(dir: &Path, name: &str, mission: &MissionState) -> Result<PathBuf, String> {
+    let name = check_name(name)?;
+    fs::create_dir_all(dir).map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+    let saved_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|since| since.as_secs()).unwrap_or(0);
+    let body = v1::SaveFile::new(saved_at, mission);
+    let body = ron::ser::to_string_pretty(&body, PrettyConfig::default()).map_err(|e| e.to_string())?;
+    let text = format!("{} {}\n{}", HEADER, VERSION, body);
+    let path = path(dir, &name);
+    let partial = path.with_extension("partial");
+    fs::write(&partial, text).map_err(|e| format!("failed to write {}: {}", partial.display(), e))?;
+    fs::rename(&partial, &path).map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// the mission saved at path, remembering the slot so saving again overwrites it
+pub fn load(path: &Path) -> Result<MissionState, String> {
+    let mut mission = read(path)?.into_mission();
+    mission.slot = path.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+    Ok(mission)
+}
+
+/// the mission in a save file's text, as if loaded from slot
+pub fn restore(slot: Option<String>, text: &str) -> Result<MissionState, String> {
+    let mut mission = from_text(text).map_err(|e| format!("the save can't be loaded: {}", e))?.into_mission();
+    mission.slot = slot;
+    Ok(mission)
+}
+
+/// the save at path, upgraded to the current format
+fn read(path: &Path) -> Result<v1::SaveFile, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    from_text(&text).map_err(|e| format!("{} can't be loaded: {}", path.display(), e))
+}
+
+/// a whole save file's text, upgraded to the current format. saves without a header are version 0
+fn from_text(text: &str) -> Result<v1::SaveFile, String> {
+    let (version, body) = match text.split_once('\n') {
+        Some((first, body)) if first.starts_with(HEADER) => {
+            let version = first[HEADER.len()..].trim();
+            match version.parse() {
+                Ok(0) => return Err("format 0 doesn't exist, version 0 saves have no header".to_string()),
+                Ok(version) => (version, body),
+                Err(_) => return Err(format!("it has a bad version, {}", version)),
+            }
+        },
+        _ => (0, text),
+    };
+    upgrade(version, body)
+}
+
+/// read a body written in format version, and migrate it one version at a time up to VERSION
+fn upgrade(version: u32, body: &str) -> Result<v1::SaveFile, String> {
+    let mut body = match version {
+        0 => Body::V0(parse(body).map_err(|e| format!("it isn't a save, or it's corrupt ({})", e))?),
+        1 => Body::V1(parse(body).map_err(|e| format!("it's corrupt ({})", e))?),
+        newer => {
+            return Err(format!("it's from a newer version of the game (format {}, this one reads up to {})", newer, VERSION))
+        },
+    };
+    loop {
+        body = match body {
+            Body::V0(file) => Body::V1(file.migrate()),
+            Body::V1(file) => return Ok(file),
+        };
+    }
+}
+
+fn parse<T: DeserializeOwned>(body: &str) -> Result<T, String> {
+    ron::de::from_str(body).map_err(|e| e.to_string())
+}
+
+/// every file in dir which looks like a save, most recent first, with any which can't be loaded
+/// last. a dir which doesn't exist yet just has no saves
+pub fn list(dir: &Path) -> Result<Vec<Slot>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) if !dir.exists() => return Ok(Vec::new()),
+        Err(e) => return Err(format!("failed to read {}: {}", dir.display(), e)),
+    };
+    let mut slots = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| format!("failed to read {}: {}", dir.display(), e))?.path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some(EXTENSION) {
+            continue
+        }
+        let name = match path.file_stem() {
+            Some(stem) => stem.to_string_lossy().into_owned(),
+            None => continue,
+        };
+        let details = read(&path).map(|file| {
+            let saved_at = file.saved_at;
+            let mission = file.into_mission();
+            Details { saved_at, difficulty: mission.difficulty, progress: mission.progress() }
+        });
+        if let Err(e) = &details {
+            warn!("{}", e);
+        }
+        slots.push(Slot { name, path, details });
+    }
+    slots.sort_by_key(|slot| {
+        let saved_at = slot.details.as_ref().map(|details| details.saved_at).ok();
+        (saved_at.is_none(), Reverse(saved_at), slot.name.clone())
+    });
+    Ok(slots)
+}
+
+pub fn delete(dir: &Path, name: &str) -> Result<(), String> {
+    let path = path(dir, name);
+    fs::remove_file(&path).map_err(|e| format!("failed to delete {}: {}", path.display(), e))
+}
+
+/// move the save called from to the slot called to, which has to be free
+pub fn rename(dir: &Path, from: &str, to: &str) -> Result<String, String> {
+    let to = check_name(to)?;
+    let (old, new) = (path(dir, from), path(dir, &to));
+    if new.exists() {
+        return Err(format!("there's already a save called {}", to))
+    }
+    fs::rename(&old, &new).map_err(|e| format!("failed to rename {}: {}", old.display(), e))?;
+    Ok(to)
+}
+
+/// seconds since the unix epoch as a UTC date and time, e.g. "2026-10-18 08:25 UTC"
+pub fn format_time(secs: u64) -> String {
+    let (days, secs) = (secs / 86_400, secs % 86_400);
+    // days since the epoch to a date in the proleptic gregorian calendar, counting in 400 year eras
+    // from 0000-03-01 so leap days fall at the end of each year
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02} {:02}:{:02} UTC", year, month, day, secs / 3600, secs % 3600 / 60)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::process;
+
+    use rand::Rng;
+
+    use crate::mission::MissionPhase;
+
+    use super::*;
+
+    /// a save from before the header, as version 0 wrote it
+    const V0_SAVE: &str = "(
+    saved_at: 1792312308,
+    mission: (
+        difficulty: Medium,
+        seed: 42,
+        rng: (
+            state: 16774380568097811214,
+            increment: 13264228356429297899,
+        ),
+        phase: Planning(
+            budget: 500,
+        ),
+    ),
+)";
+
+    /// an empty directory for one test's saves
+    fn test_dir(test: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("mars-{}-{}", test, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn error(text: &str) -> String {
+        from_text(text).err().expect("the save should have been refused")
+    }
+
+    #[test]
+    fn saved_missions_load_as_they_were() {
+        let dir = test_dir("round-trip");
+        let mut mission = MissionState::new(MissionDifficulty::Hard, u64::MAX);
+        mission.phase = MissionPhase::Execution { elapsed: 12.5 };
+        let path = save(&dir, " outpost ", &mission).unwrap();
+        assert!(fs::read_to_string(&path).unwrap().starts_with("mars save 1\n"));
+
+        let mut loaded = load(&path).unwrap();
+        assert_eq!(loaded.difficulty, MissionDifficulty::Hard);
+        assert_eq!(loaded.seed, u64::MAX);
+        assert_eq!(loaded.phase, MissionPhase::Execution { elapsed: 12.5 });
+        assert_eq!(loaded.slot.as_deref(), Some("outpost"));
+        assert_eq!(loaded.rng.gen::<u64>(), mission.rng.gen::<u64>());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn saves_without_a_header_are_migrated() {
+        let file = match from_text(V0_SAVE) {
+            Ok(file) => file,
+            Err(e) => panic!("version 0 saves should load, not {}", e),
+        };
+        assert_eq!(file.saved_at, 1792312308);
+        let mission = file.into_mission();
+        assert_eq!(mission.difficulty, MissionDifficulty::Medium);
+        assert_eq!(mission.seed, 42);
+        assert_eq!(mission.phase, MissionPhase::Planning { budget: 500.0 });
+    }
+
+    #[test]
+    fn bad_versions_are_refused() {
+        assert_eq!(error(&format!("mars save one\n{}", V0_SAVE)), "it has a bad version, one");
+        assert_eq!(
+            error(&format!("mars save 2\n{}", V0_SAVE)),
+            "it's from a newer version of the game (format 2, this one reads up to 1)",
+        );
+        assert_eq!(error(&format!("mars save 0\n{}", V0_SAVE)), "format 0 doesn't exist, version 0 saves have no header");
+    }
+
+    #[test]
+    fn corrupt_bodies_are_refused() {
+        assert!(error("mars save 1\n(saved_at: 5, mission: (").starts_with("it's corrupt"));
+        assert!(error("some notes").starts_with("it isn't a save, or it's corrupt"));
+    }
+
+    #[test]
+    fn saves_which_cant_be_loaded_are_listed_last() {
+        let dir = test_dir("list");
+        save(&dir, "good", &MissionState::new(MissionDifficulty::Easy, 1)).unwrap();
+        fs::write(dir.join("broken.ron"), "mars save 1\n(saved_at: 5, mission: (").unwrap();
+        fs::write(dir.join("notes.txt"), "not a save").unwrap();
+
+        let slots = list(&dir).unwrap();
+        let names: Vec<&str> = slots.iter().map(|slot| slot.name.as_str()).collect();
+        assert_eq!(names, ["good", "broken"]);
+        assert_eq!(slots[0].details.as_ref().map(|details| details.difficulty).ok(), Some(MissionDifficulty::Easy));
+        assert!(slots[1].details.as_ref().unwrap_err().contains("it's corrupt"));
+        assert_eq!(slots[1].summary(), "broken - can't be loaded");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn times_are_formatted_as_utc() {
+        assert_eq!(format_time(0), "1970-01-01 00:00 UTC");
+        assert_eq!(format_time(951_782_400), "2000-02-29 00:00 UTC");
+        assert_eq!(format_time(1_700_000_000), "2023-11-14 22:13 UTC");
+        assert_eq!(format_time(4_102_444_799), "2099-12-31 23:59 UTC");
+    }
+}
@@ -0,0 +1,55 @@
+use rand_pcg::Pcg32;
+use serde::Deserialize;
+
+use crate::saves::v1;
+
+
+/// format version 0, from before saves had a header
+#[derive(Deserialize)]
+pub struct SaveFile {
+    saved_at: u64,
+    mission: Mission,
+}
+
+#[derive(Deserialize)]
+struct Mission {
+    difficulty: Difficulty,
+    seed: u64,
+    rng: Pcg32,
+    phase: Phase,
+}
+
+#[derive(Deserialize)]
+enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+#[derive(Deserialize)]
+enum Phase {
+    Planning {
+        budget: f32,
+    },
+    Execution {
+        elapsed: f64,
+    },
+}
+
+
+impl SaveFile {
+    /// version 1 only added the header, the body is the same
+    pub fn migrate(self) -> v1::SaveFile {
+        let Mission { difficulty, seed, rng, phase } = self.mission;
+        let difficulty = match difficulty {
+            Difficulty::Easy => v1::Difficulty::Easy,
+            Difficulty::Medium => v1::Difficulty::Medium,
+            Difficulty::Hard => v1::Difficulty::Hard,
+        };
+        let phase = match phase {
+            Phase::Planning { budget } => v1::Phase::Planning { budget },
+            Phase::Execution { elapsed } => v1::Phase::Execution { elapsed },
+        };
+        v1::SaveFile { saved_at: self.saved_at, mission: v1::Mission { difficulty, seed, rng, phase } }
+    }
+}
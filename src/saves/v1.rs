@@ -0,0 +1,73 @@
+use rand_pcg::Pcg32;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::app::MissionDifficulty;
+use crate::mission::MissionPhase;
+use crate::mission::MissionState;
+
+
+/// format version 1, the current one. written after a header line naming the version
+#[derive(Deserialize, Serialize)]
+pub struct SaveFile {
+    /// seconds since the unix epoch
+    pub saved_at: u64,
+    pub mission: Mission,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Mission {
+    pub difficulty: Difficulty,
+    pub seed: u64,
+    pub rng: Pcg32,
+    pub phase: Phase,
+}
+
+#[derive(Deserialize, Serialize)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+#[derive(Deserialize, Serialize)]
+pub enum Phase {
+    Planning {
+        budget: f32,
+    },
+    Execution {
+        elapsed: f64,
+    },
+}
+
+
+impl SaveFile {
+    pub fn new(saved_at: u64, mission: &MissionState) -> SaveFile {
+        let difficulty = match mission.difficulty {
+            MissionDifficulty::Easy => Difficulty::Easy,
+            MissionDifficulty::Medium => Difficulty::Medium,
+            MissionDifficulty::Hard => Difficulty::Hard,
+        };
+        let phase = match mission.phase {
+            MissionPhase::Planning { budget } => Phase::Planning { budget },
+            MissionPhase::Execution { elapsed } => Phase::Execution { elapsed },
+        };
+        let mission = Mission { difficulty, seed: mission.seed, rng: mission.rng.clone(), phase };
+        SaveFile { saved_at, mission }
+    }
+
+    /// the mission as it was saved
+    pub fn into_mission(self) -> MissionState {
+        let Mission { difficulty, seed, rng, phase } = self.mission;
+        let difficulty = match difficulty {
+            Difficulty::Easy => MissionDifficulty::Easy,
+            Difficulty::Medium => MissionDifficulty::Medium,
+            Difficulty::Hard => MissionDifficulty::Hard,
+        };
+        let phase = match phase {
+            Phase::Planning { budget } => MissionPhase::Planning { budget },
+            Phase::Execution { elapsed } => MissionPhase::Execution { elapsed },
+        };
+        MissionState::resume(difficulty, seed, rng, phase)
+    }
+}
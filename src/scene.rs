@@ -0,0 +1,333 @@
+use graphics::Context;
+use piston::input::ControllerAxisArgs;
+
+use crate::actions::Action;
+use crate::axis::Axis;
+use crate::events::EventBus;
+use crate::hover::Hover;
+use crate::load::LoadState;
+use crate::menu::MenuState;
+use crate::mission::MissionState;
+use crate::options::OptionsState;
+use crate::press::Press;
+use crate::release::Release;
+use crate::render::Backend;
+use crate::render::Render;
+use crate::resize::Resize;
+use crate::scroll::Scroll;
+use crate::text::Text;
+use crate::update::Update;
+
+
+/// a full screen of the game. scenes are layered on a SceneStack, so a menu can sit on top of a
+/// mission without either knowing about the other
+pub enum Scene {
+    Menu(MenuState),
+    Mission(MissionState),
+    Options(OptionsState),
+    Load(LoadState),
+}
+
+impl Scene {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scene::Menu(_) => "menu",
+            Scene::Mission(_) => "mission",
+            Scene::Options(_) => "options",
+            Scene::Load(_) => "load",
+        }
+    }
+
+    /// text or id of the widget under the cursor, if any
+    pub fn hovered(&self) -> Option<&str> {
+        match self {
+            Scene::Menu(menu_state) => menu_state.hovered_text(),
+            Scene::Mission(_) => None,
+            Scene::Options(options_state) => options_state.hovered(),
+            Scene::Load(load_state) => load_state.hovered(),
+        }
+    }
+
+    /// whether the scenes beneath this one show through and should be rendered before it
+    pub fn transparent(&self) -> bool {
+        match self {
+            // the pause menu shades the mission rather than hiding it
+            Scene::Menu(menu_state) => menu_state.active_mission,
+            Scene::Mission(_) => false,
+            Scene::Options(_) => false,
+            Scene::Load(_) => false,
+        }
+    }
+
+    /// whether input stops at this scene rather than also reaching the scenes beneath it. scenes
+    /// beneath a blocking scene are also paused
+    pub fn blocks_input(&self) -> bool {
+        match self {
+            Scene::Menu(_) => true,
+            Scene::Mission(_) => true,
+            Scene::Options(_) => true,
+            Scene::Load(_) => true,
+        }
+    }
+}
+
+impl Hover for Scene {
+    fn hover(&mut self, window_size: [f64; 2], cursor: [f64; 2], bus: &mut EventBus) {
+        match self {
+            Scene::Menu(menu_state) => menu_state.hover(window_size, cursor, bus),
+            Scene::Mission(mission_state) => mission_state.hover(window_size, cursor, bus),
+            Scene::Options(options_state) => options_state.hover(window_size, cursor, bus),
+            Scene::Load(load_state) => load_state.hover(window_size, cursor, bus),
+        }
+    }
+}
+
+impl Press for Scene {
+    fn press(&mut self, action: Action, bus: &mut EventBus) {
+        match self {
+            Scene::Menu(menu_state) => menu_state.press(action, bus),
+            Scene::Mission(mission_state) => mission_state.press(action, bus),
+            Scene::Options(options_state) => options_state.press(action, bus),
+            Scene::Load(load_state) => load_state.press(action, bus),
+        }
+    }
+}
+
+impl Release for Scene {
+    fn release(&mut self, action: Action, bus: &mut EventBus) {
+        match self {
+            Scene::Menu(menu_state) => menu_state.release(action, bus),
+            Scene::Mission(mission_state) => mission_state.release(action, bus),
+            Scene::Options(options_state) => options_state.release(action, bus),
+            Scene::Load(load_state) => load_state.release(action, bus),
+        }
+    }
+}
+
+impl Text for Scene {
+    fn text(&mut self, text: &str, bus: &mut EventBus) {
+        match self {
+            Scene::Menu(menu_state) => menu_state.text(text, bus),
+            Scene::Mission(mission_state) => mission_state.text(text, bus),
+            Scene::Options(options_state) => options_state.text(text, bus),
+            Scene::Load(load_state) => load_state.text(text, bus),
+        }
+    }
+}
+
+impl Resize for Scene {
+    fn resize(&mut self, window_size: [f64; 2]) {
+        match self {
+            Scene::Menu(menu_state) => menu_state.resize(window_size),
+            Scene::Mission(mission_state) => mission_state.resize(window_size),
+            Scene::Options(options_state) => options_state.resize(window_size),
+            Scene::Load(load_state) => load_state.resize(window_size),
+        }
+    }
+}
+
+impl Scroll for Scene {
+    fn scroll(&mut self, delta: [f64; 2], bus: &mut EventBus) {
+        match self {
+            Scene::Menu(_) => {},
+            Scene::Mission(mission_state) => mission_state.scroll(delta, bus),
+            Scene::Options(options_state) => options_state.scroll(delta, bus),
+            Scene::Load(load_state) => load_state.scroll(delta, bus),
+        }
+    }
+}
+
+impl Axis for Scene {
+    fn axis(&mut self, args: ControllerAxisArgs, bus: &mut EventBus) {
+        match self {
+            Scene::Menu(_) | Scene::Options(_) | Scene::Load(_) => {},
+            Scene::Mission(mission_state) => mission_state.axis(args, bus),
+        }
+    }
+}
+
+impl Update for Scene {
+    fn update(&mut self, dt: f64, bus: &mut EventBus) {
+        match self {
+            Scene::Menu(menu_state) => menu_state.update(dt, bus),
+            Scene::Mission(mission_state) => mission_state.update(dt, bus),
+            Scene::Options(_) | Scene::Load(_) => {},
+        }
+    }
+}
+
+impl Render for Scene {
+    fn render<G: Backend>(&self, c: &Context, g: &mut G) {
+        match self {
+            Scene::Menu(menu_state) => menu_state.render(c, g),
+            Scene::Mission(mission_state) => mission_state.render(c, g),
+            Scene::Options(options_state) => options_state.render(c, g),
+            Scene::Load(load_state) => load_state.render(c, g),
+        }
+    }
+}
+
+
+/// scenes from bottom to top. input and updates go from the top down until a scene blocks them,
+/// rendering goes from the topmost opaque scene up
+pub struct SceneStack {
+    scenes: Vec<Scene>,
+    /// the size every scene is laid out for, including scenes pushed later
+    window_size: [f64; 2],
+}
+
+impl SceneStack {
+    pub fn new(root: Scene) -> SceneStack {
+        SceneStack { scenes: vec![root], window_size: [0.0, 0.0] }
+    }
+
+    pub fn push(&mut self, mut scene: Scene) {
+        info!("pushed {} scene", scene.name());
+        scene.resize(self.window_size);
+        self.scenes.push(scene);
+    }
+
+    pub fn pop(&mut self) -> Option<Scene> {
+        let old = self.scenes.pop();
+        if let Some(scene) = &old {
+            info!("popped {} scene", scene.name());
+        }
+        old
+    }
+
+    /// swap the top scene for another, returning the old one
+    pub fn replace(&mut self, mut scene: Scene) -> Option<Scene> {
+        let old = self.scenes.pop();
+        match &old {
+            Some(old) => info!("replaced {} scene with {} scene", old.name(), scene.name()),
+            None => info!("pushed {} scene", scene.name()),
+        }
+        scene.resize(self.window_size);
+        self.scenes.push(scene);
+        old
+    }
+
+    /// pop every scene above the bottom one
+    pub fn unwind(&mut self) {
+        while self.scenes.len() > 1 {
+            self.pop();
+        }
+    }
+
+    pub fn top(&self) -> Option<&Scene> {
+        self.scenes.last()
+    }
+
+    pub fn top_mut(&mut self) -> Option<&mut Scene> {
+        self.scenes.last_mut()
+    }
+
+    pub fn mission(&self) -> Option<&MissionState> {
+        self.scenes.iter().rev().find_map(|scene| match scene {
+            Scene::Mission(mission_state) => Some(mission_state),
+            _ => None,
+        })
+    }
+
+    pub fn mission_mut(&mut self) -> Option<&mut MissionState> {
+        self.scenes.iter_mut().rev().find_map(|scene| match scene {
+            Scene::Mission(mission_state) => Some(mission_state),
+            _ => None,
+        })
+    }
+
+    /// names of every scene, bottom first
+    pub fn names(&self) -> Vec<&'static str> {
+        self.scenes.iter().map(|scene| scene.name()).collect()
+    }
+
+    /// text of the hovered widget in the top scene, if any
+    pub fn hovered(&self) -> Option<&str> {
+        self.top().and_then(|scene| scene.hovered())
+    }
+
+    /// the scenes which should receive input and updates, topmost first
+    fn active(&mut self) -> impl Iterator<Item=&mut Scene> {
+        let mut blocked = false;
+        self.scenes.iter_mut().rev().take_while(move |scene| {
+            let take = !blocked;
+            blocked = blocked || scene.blocks_input();
+            take
+        })
+    }
+}
+
+impl Hover for SceneStack {
+    fn hover(&mut self, window_size: [f64; 2], cursor: [f64; 2], bus: &mut EventBus) {
+        for scene in self.active() {
+            scene.hover(window_size, cursor, bus);
+        }
+    }
+}
+
+impl Press for SceneStack {
+    fn press(&mut self, action: Action, bus: &mut EventBus) {
+        for scene in self.active() {
+            scene.press(action, bus);
+        }
+    }
+}
+
+impl Release for SceneStack {
+    fn release(&mut self, action: Action, bus: &mut EventBus) {
+        for scene in self.active() {
+            scene.release(action, bus);
+        }
+    }
+}
+
+impl Text for SceneStack {
+    fn text(&mut self, text: &str, bus: &mut EventBus) {
+        for scene in self.active() {
+            scene.text(text, bus);
+        }
+    }
+}
+
+impl Resize for SceneStack {
+    /// every scene, not just the active ones, so scenes below are ready when they're uncovered
+    fn resize(&mut self, window_size: [f64; 2]) {
+        self.window_size = window_size;
+        for scene in self.scenes.iter_mut() {
+            scene.resize(window_size);
+        }
+    }
+}
+
+impl Scroll for SceneStack {
+    fn scroll(&mut self, delta: [f64; 2], bus: &mut EventBus) {
+        for scene in self.active() {
+            scene.scroll(delta, bus);
+        }
+    }
+}
+
+impl Axis for SceneStack {
+    fn axis(&mut self, args: ControllerAxisArgs, bus: &mut EventBus) {
+        for scene in self.active() {
+            scene.axis(args, bus);
+        }
+    }
+}
+
+impl Update for SceneStack {
+    fn update(&mut self, dt: f64, bus: &mut EventBus) {
+        for scene in self.active() {
+            scene.update(dt, bus);
+        }
+    }
+}
+
+impl Render for SceneStack {
+    fn render<G: Backend>(&self, c: &Context, g: &mut G) {
+        let bottom = self.scenes.iter().rposition(|scene| !scene.transparent()).unwrap_or(0);
+        for scene in self.scenes[bottom..].iter() {
+            scene.render(c, g);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use graphics::Context;
+use image::RgbaImage;
+
+use crate::app::AppState;
+use crate::raster::RasterGraphics;
+use crate::render::Render;
+
+
+/// a new file in dir named after the current time, e.g. screenshots/mars-1571234567890.png
+pub fn timestamped_path(dir: &Path) -> PathBuf {
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|t| t.as_millis()).unwrap_or(0);
+    dir.join(format!("mars-{}.png", millis))
+}
+
+/// write image to path as a PNG, creating its directory if needed. an image with nothing in it,
+/// e.g. of a minimized window, isn't saved
+pub fn save(image: &RgbaImage, path: &Path) -> Result<(), String> {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return Err(format!("the window is {}x{}, there's nothing to save", width, height))
+    }
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| format!("failed to create {}: {}", dir.display(), e))?;
+    }
+    image.save(path).map_err(|e| format!("failed to write {}: {}", path.display(), e))
+}
+
+/// read back what has been drawn to the current OpenGL framebuffer. call after drawing and before
+/// the buffers are swapped. fails if the framebuffer is empty, e.g. while the window is minimized
+pub fn capture_gl(width: u32, height: u32) -> Result<RgbaImage, String> {
+    if width == 0 || height == 0 {
+        return Err(format!("the window is {}x{}, there's nothing to capture", width, height))
+    }
+    let mut pixels = vec![0u8; width as usize * height as usize * 4];
+    unsafe {
+        gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+        gl::ReadPixels(0, 0, width as i32, height as i32, gl::RGBA, gl::UNSIGNED_BYTE, pixels.as_mut_ptr() as *mut _);
+    }
+    // OpenGL rows start at the bottom, images start at the top
+    let row = width as usize * 4;
+    let flipped = pixels.chunks(row).rev().flatten().cloned().collect();
+    Ok(RgbaImage::from_raw(width, height, flipped).expect("pixel buffer matches the image size"))
+}
+
+/// draw the app at its current window size on the CPU, without a window or GPU
+pub fn render(app: &AppState) -> RgbaImage {
+    let [w, h] = app.window_size;
+    let mut raster = RasterGraphics::new(w as u32, h as u32);
+    app.render(&Context::new_abs(w, h), &mut raster);
+    raster.buffer
+}
@@ -0,0 +1,7 @@
+use crate::events::EventBus;
+
+
+/// trait for types which respond to the mouse wheel. delta is in lines, positive y scrolls up
+pub trait Scroll {
+    fn scroll(&mut self, delta: [f64; 2], bus: &mut EventBus);
+}
@@ -1,14 +1,15 @@
-use std::boxed::Box;
-use std::io::BufReader;
-use std::fs::File;
-use std::path::PathBuf;
-use std::collections::HashMap;
-use std::io::Read;
 use std::io::Cursor;
+use std::sync::Arc;
 
 use rodio::Device;
 use rodio::Sink;
-use rodio::Source;
+
+use crate::app::AppEvent;
+use crate::assets;
+use crate::config::Config;
+use crate::config::Setting;
+use crate::events::EventBus;
+use crate::events::Subscribe;
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Sound {
@@ -16,31 +17,139 @@ pub enum Sound {
     Holst,
 }
 
-lazy_static! {
-    static ref SOUND_DEVICE: Device = rodio::default_output_device().unwrap();
-    static ref SOUNDS: HashMap<Sound, Vec<u8>> = load_sounds();
+impl Sound {
+    pub const ALL: [Sound; 2] = [Sound::TapMuted, Sound::Holst];
+
+    /// path relative to the asset root
+    pub fn file(self) -> &'static str {
+        match self {
+            Sound::TapMuted => "sounds/tap-muted.wav",
+            Sound::Holst => "sounds/holst.mp3",
+        }
+    }
+
+    /// whether the sound is played at the music volume rather than the sound effects volume
+    pub fn is_music(self) -> bool {
+        match self {
+            Sound::TapMuted => false,
+            Sound::Holst => true,
+        }
+    }
 }
 
-fn load_sounds() -> HashMap<Sound, Vec<u8>> {
-    let sounds_dir = find_folder::Search::ParentsThenKids(3, 3).for_folder("sounds").unwrap();
-    let mut sounds: HashMap<Sound, Vec<u8>> = HashMap::new();
-    for (sound, filename) in [
-        (Sound::TapMuted, "tap-muted.wav"),
-        (Sound::Holst, "holst.mp3"),
-    ].iter() {
-        let mut bytes: Vec<u8> = Vec::new();
-        File::open(sounds_dir.join(filename)).unwrap().read_to_end(&mut bytes);
-        sounds.insert(*sound, bytes);
+/// the encoded bytes of a sound file, shared between every sink playing it
+#[derive(Clone)]
+pub struct SoundData(Arc<Vec<u8>>);
+
+impl SoundData {
+    pub fn new(bytes: Vec<u8>) -> SoundData {
+        SoundData(Arc::new(bytes))
     }
-    sounds
 }
 
-pub fn play_sound(sound: Sound, volume: f32) {
-    let file: Cursor<&[u8]> = Cursor::new(&SOUNDS[&sound]);
-    let source = rodio::Decoder::new(BufReader::new(file)).unwrap();
-    let sink = Sink::new(&SOUND_DEVICE);
-    sink.set_volume(volume);
-    sink.append(source);
-    sink.play();
-    sink.detach();
-}
\ No newline at end of file
+impl AsRef<[u8]> for SoundData {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// where sounds are played. machines without an audio device get Null, which swallows them
+pub enum AudioOutput {
+    Device(Device),
+    Null,
+}
+
+impl AudioOutput {
+    /// the default output device, or Null with a warning if there isn't one which can play
+    /// anything. rodio panics on the first sound played to a device without a format, so that's
+    /// checked here
+    pub fn open() -> AudioOutput {
+        let device = match rodio::default_output_device() {
+            Some(device) => device,
+            None => {
+                warn!("no audio output device found, sounds will be silent");
+                return AudioOutput::Null
+            },
+        };
+        match device.default_output_format() {
+            Ok(_) => AudioOutput::Device(device),
+            Err(e) => {
+                warn!("no usable audio output device found ({}), sounds will be silent", e);
+                AudioOutput::Null
+            },
+        }
+    }
+
+    /// play a sound, or nothing if it failed to load or there's nowhere to play it. the sound stops
+    /// if the returned sink is dropped, unless it's detached
+    pub fn play(&self, sound: Sound, volume: f32) -> Option<Sink> {
+        let device = match self {
+            AudioOutput::Device(device) => device,
+            AudioOutput::Null => return None,
+        };
+        let data = assets::get().sound(sound)?.clone();
+        let source = match rodio::Decoder::new(Cursor::new(data)) {
+            Ok(source) => source,
+            Err(e) => {
+                error!("failed to decode {:?}: {}", sound, e);
+                return None
+            },
+        };
+        let sink = Sink::new(device);
+        sink.set_volume(volume);
+        sink.append(source);
+        sink.play();
+        Some(sink)
+    }
+}
+
+/// plays sounds requested through AppEvent::PlaySound, at the volumes from the config. volume
+/// changes apply to sounds played after them
+pub struct Audio {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub output: AudioOutput,
+    /// the music playing, if any, kept so its volume can be changed. starting other music stops it
+    music: Option<Sink>,
+}
+
+impl Audio {
+    pub fn new(config: &Config) -> Audio {
+        Audio {
+            master_volume: config.master_volume,
+            music_volume: config.music_volume,
+            sfx_volume: config.sfx_volume,
+            output: AudioOutput::open(),
+            music: None,
+        }
+    }
+
+    /// the volume for music or sound effects, including the master volume
+    fn volume(&self, music: bool) -> f32 {
+        let volume = if music { self.music_volume } else { self.sfx_volume };
+        (self.master_volume * volume).clamp(0.0, 1.0)
+    }
+}
+
+impl Subscribe for Audio {
+    fn notify(&mut self, event: &AppEvent, _bus: &mut EventBus) {
+        match event {
+            AppEvent::PlaySound(sound) => {
+                let sink = self.output.play(*sound, self.volume(sound.is_music()));
+                if sound.is_music() {
+                    self.music = sink;
+                } else if let Some(sink) = sink {
+                    sink.detach();
+                }
+            },
+            AppEvent::ChangeSetting(Setting::MasterVolume(volume)) => self.master_volume = *volume,
+            AppEvent::ChangeSetting(Setting::MusicVolume(volume)) => self.music_volume = *volume,
+            AppEvent::ChangeSetting(Setting::SfxVolume(volume)) => self.sfx_volume = *volume,
+            _ => {},
+        }
+        if let Some(music) = &self.music {
+            music.set_volume(self.volume(true));
+        }
+    }
+}
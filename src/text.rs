@@ -0,0 +1,7 @@
+use crate::events::EventBus;
+
+
+/// trait for types which accept typed text
+pub trait Text {
+    fn text(&mut self, text: &str, bus: &mut EventBus);
+}
@@ -1,6 +1,65 @@
-use crate::app::AppEvent;
+use crate::events::EventBus;
 
 
+/// length of one simulation step in seconds. the simulation only ever advances by exactly this much,
+/// so it behaves the same no matter how often updates and renders actually happen
+pub const TIMESTEP: f64 = 1.0 / 120.0;
+
+/// upper bound on the steps taken for a single update, so that after a long stall (e.g. the window
+/// being dragged) we drop time instead of freezing while we catch up
+const MAX_STEPS_PER_UPDATE: u32 = 12;
+
+
+/// trait for types which advance over time. dt is in seconds
 pub trait Update {
-    fn update(&mut self) -> Option<AppEvent>;
-}
\ No newline at end of file
+    fn update(&mut self, dt: f64, bus: &mut EventBus);
+}
+
+
+/// accumulates real elapsed time and hands it back out in whole TIMESTEP sized steps
+#[derive(Default)]
+pub struct FixedTimestep {
+    accumulator: f64,
+}
+
+impl FixedTimestep {
+    pub fn new() -> FixedTimestep {
+        FixedTimestep { accumulator: 0.0 }
+    }
+
+    /// add dt seconds of real time and return how many simulation steps are due
+    pub fn advance(&mut self, dt: f64) -> u32 {
+        self.accumulator += dt;
+        let mut steps = 0;
+        while self.accumulator >= TIMESTEP {
+            self.accumulator -= TIMESTEP;
+            steps += 1;
+            if steps == MAX_STEPS_PER_UPDATE {
+                self.accumulator = 0.0;
+                break
+            }
+        }
+        steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leftover_time_carries_over() {
+        let mut clock = FixedTimestep::new();
+        assert_eq!(clock.advance(TIMESTEP * 2.5), 2);
+        assert_eq!(clock.advance(TIMESTEP * 0.25), 0);
+        assert_eq!(clock.advance(TIMESTEP * 0.5), 1);
+        assert_eq!(clock.advance(0.0), 0);
+    }
+
+    #[test]
+    fn long_stalls_are_dropped() {
+        let mut clock = FixedTimestep::new();
+        assert_eq!(clock.advance(10.0), MAX_STEPS_PER_UPDATE);
+        assert_eq!(clock.advance(0.0), 0);
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use crate::assets;
+use crate::assets::AssetSource;
+use crate::fonts::Font;
+use crate::images::Image;
+use crate::sounds::Sound;
+
+
+/// how often AssetWatcher checks for changed files
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+
+/// every file assets are loaded from, relative to the asset root
+fn files() -> impl Iterator<Item=&'static str> {
+    Font::ALL.iter().map(|font| font.file())
+        .chain(Image::ALL.iter().map(|image| image.file()))
+        .chain(Sound::ALL.iter().map(|sound| sound.file()))
+}
+
+
+/// reloads assets whose files change on disk, so they can be tuned while the game is running
+pub struct AssetWatcher {
+    root: PathBuf,
+    /// when each file was last modified, or None if it doesn't exist
+    modified: HashMap<&'static str, Option<SystemTime>>,
+    last_check: Instant,
+}
+
+impl AssetWatcher {
+    /// a watcher for the installed assets, or None if they weren't loaded from a directory
+    pub fn new() -> Option<AssetWatcher> {
+        let root = match &assets::get().source {
+            AssetSource::Dir(root) => root.clone(),
+            #[cfg(feature = "embedded-assets")]
+            AssetSource::Embedded => return None,
+        };
+        let modified = files().map(|file| (file, modified(&root.join(file)))).collect();
+        Some(AssetWatcher { root, modified, last_check: Instant::now() })
+    }
+
+    /// reload and install any assets whose files have changed since the last check
+    pub fn check(&mut self) {
+        if self.last_check.elapsed() < WATCH_INTERVAL {
+            return
+        }
+        self.last_check = Instant::now();
+
+        let mut changed = Vec::new();
+        for (file, last_modified) in self.modified.iter_mut() {
+            let now_modified = modified(&self.root.join(file));
+            if now_modified != *last_modified {
+                *last_modified = now_modified;
+                changed.push(*file);
+            }
+        }
+        if changed.is_empty() {
+            return
+        }
+        let mut assets = (*assets::get()).clone();
+        for file in changed {
+            debug!("{} changed", file);
+            assets.reload(file);
+        }
+        assets::install(assets);
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
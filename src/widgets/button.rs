@@ -0,0 +1,73 @@
+use graphics::Context;
+use graphics::rectangle;
+use graphics::Transformed;
+
+use crate::actions::Action;
+use crate::events::EventBus;
+use crate::fonts;
+use crate::fonts::Font;
+use crate::hover::Hover;
+use crate::press::Press;
+use crate::release::Release;
+use crate::render::Backend;
+use crate::render::Render;
+use crate::widgets;
+use crate::widgets::WidgetEvent;
+use crate::widgets::WidgetState;
+
+
+/// text which reports Clicked when activated
+pub struct Button {
+    pub id: &'static str,
+    pub text: String,
+    pub rect: [f64; 4],
+    pub state: WidgetState,
+    pub(super) events: Vec<WidgetEvent>,
+}
+
+impl Button {
+    pub fn new(id: &'static str, text: &str) -> Button {
+        Button { id, text: text.to_string(), rect: [0.0; 4], state: WidgetState::default(), events: Vec::new() }
+    }
+}
+
+impl Render for Button {
+    fn render<G: Backend>(&self, c: &Context, g: &mut G) {
+        let [x, _, _, h] = self.rect;
+        let (size, padding) = (widgets::font_size(h), widgets::padding(h));
+        let y = widgets::baseline(self.rect, size);
+        let color = self.state.color();
+        g.draw_text(color, size, &self.text, Font::Regular, c.transform.trans(x + padding, y));
+        if self.state.focused || self.state.pressed {
+            let width = fonts::width(Font::Regular, size, &self.text);
+            rectangle(color, [x + padding, y + widgets::LINE * 2.0, width, widgets::LINE], c.transform, g);
+        }
+    }
+}
+
+impl Hover for Button {
+    fn hover(&mut self, _window_size: [f64; 2], cursor: [f64; 2], _bus: &mut EventBus) {
+        self.state.hovered = widgets::contains(self.rect, cursor);
+    }
+}
+
+impl Press for Button {
+    fn press(&mut self, action: Action, _bus: &mut EventBus) {
+        if let Action::Select = action {
+            self.state.press();
+        }
+    }
+}
+
+impl Release for Button {
+    fn release(&mut self, action: Action, _bus: &mut EventBus) {
+        let activated = match action {
+            Action::Select => self.state.release(),
+            Action::Confirm => self.state.active(),
+            _ => false,
+        };
+        if activated {
+            self.events.push(WidgetEvent::Clicked(self.id));
+        }
+    }
+}
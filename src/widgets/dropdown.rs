@@ -0,0 +1,154 @@
+use graphics::Context;
+use graphics::Rectangle;
+use graphics::rectangle;
+use graphics::Transformed;
+
+use crate::actions::Action;
+use crate::colors;
+use crate::events::EventBus;
+use crate::fonts::Font;
+use crate::hover::Hover;
+use crate::press::Press;
+use crate::release::Release;
+use crate::render::Backend;
+use crate::render::Render;
+use crate::widgets;
+use crate::widgets::WidgetEvent;
+use crate::widgets::WidgetState;
+
+
+/// the selected option in a box, which opens into a list of every option below it. reports
+/// Selected with the index chosen
+pub struct Dropdown {
+    pub id: &'static str,
+    pub options: Vec<String>,
+    pub selected: usize,
+    /// whether the options are showing
+    pub open: bool,
+    /// the option under the cursor or moved to with Up and Down while open
+    highlighted: Option<usize>,
+    /// where the cursor last was, to tell which option a click lands on
+    cursor: [f64; 2],
+    pub rect: [f64; 4],
+    pub state: WidgetState,
+    pub(super) events: Vec<WidgetEvent>,
+}
+
+impl Dropdown {
+    pub fn new(id: &'static str, options: Vec<String>, selected: usize) -> Dropdown {
+        Dropdown {
+            id,
+            options,
+            selected,
+            open: false,
+            highlighted: None,
+            cursor: [0.0, 0.0],
+            rect: [0.0; 4],
+            state: WidgetState::default(),
+            events: Vec::new(),
+        }
+    }
+
+    /// [x, y, w, h] of option i while open, one row each below the box
+    fn option_rect(&self, i: usize) -> [f64; 4] {
+        let [x, y, w, h] = self.rect;
+        [x, y + h * (i + 1) as f64, w, h]
+    }
+
+    fn option_at(&self, cursor: [f64; 2]) -> Option<usize> {
+        (0..self.options.len()).find(|i| widgets::contains(self.option_rect(*i), cursor))
+    }
+
+    fn set_open(&mut self, open: bool) {
+        self.open = open;
+        self.highlighted = if open { Some(self.selected) } else { None };
+    }
+
+    fn choose(&mut self, i: usize) {
+        self.set_open(false);
+        if i != self.selected {
+            self.selected = i;
+            self.events.push(WidgetEvent::Selected(self.id, i));
+        }
+    }
+}
+
+impl Render for Dropdown {
+    fn render<G: Backend>(&self, c: &Context, g: &mut G) {
+        let color = self.state.color();
+        Rectangle::new_border(color, widgets::LINE / 2.0).draw(self.rect, &c.draw_state, c.transform, g);
+        let text = format!("{} {}", self.options.get(self.selected).map(String::as_str).unwrap_or(""), if self.open { "^" } else { "v" });
+        let (size, padding) = (widgets::font_size(self.rect[3]), widgets::padding(self.rect[3]));
+        let y = widgets::baseline(self.rect, size);
+        g.draw_text(color, size, &text, Font::Regular, c.transform.trans(self.rect[0] + padding, y));
+        if !self.open {
+            return
+        }
+        for (i, option) in self.options.iter().enumerate() {
+            let rect = self.option_rect(i);
+            rectangle(widgets::BACKGROUND, rect, c.transform, g);
+            let option_color = if self.highlighted == Some(i) { colors::palette().mars } else { colors::palette().red };
+            let y = widgets::baseline(rect, size);
+            g.draw_text(option_color, size, option, Font::Regular, c.transform.trans(rect[0] + padding, y));
+        }
+        let [x, y, w, h] = self.rect;
+        let list = [x, y + h, w, h * self.options.len() as f64];
+        Rectangle::new_border(color, widgets::LINE / 2.0).draw(list, &c.draw_state, c.transform, g);
+    }
+}
+
+impl Hover for Dropdown {
+    fn hover(&mut self, _window_size: [f64; 2], cursor: [f64; 2], _bus: &mut EventBus) {
+        self.state.hovered = widgets::contains(self.rect, cursor);
+        self.cursor = cursor;
+        if self.open {
+            if let Some(i) = self.option_at(cursor) {
+                self.highlighted = Some(i);
+            }
+        }
+    }
+}
+
+impl Press for Dropdown {
+    fn press(&mut self, action: Action, _bus: &mut EventBus) {
+        let n = self.options.len();
+        match action {
+            Action::Select => self.state.press(),
+            Action::Up if self.open && n > 0 => {
+                self.highlighted = Some(self.highlighted.map_or(n - 1, |i| (i + n - 1) % n));
+            },
+            Action::Down if self.open && n > 0 => {
+                self.highlighted = Some(self.highlighted.map_or(0, |i| (i + 1) % n));
+            },
+            _ => {},
+        }
+    }
+}
+
+impl Release for Dropdown {
+    fn release(&mut self, action: Action, _bus: &mut EventBus) {
+        match action {
+            Action::Select if self.open => {
+                // a click on an option chooses it, a click anywhere else closes the list
+                self.state.release();
+                match self.option_at(self.cursor) {
+                    Some(i) => self.choose(i),
+                    None => self.set_open(false),
+                }
+            },
+            Action::Select => {
+                let clicked = self.state.release();
+                if clicked {
+                    self.set_open(true);
+                }
+            },
+            Action::Confirm if self.state.active() => match (self.open, self.highlighted) {
+                (true, Some(i)) => self.choose(i),
+                (true, None) => self.set_open(false),
+                (false, _) => self.set_open(true),
+            },
+            Action::Back if self.open => self.set_open(false),
+            _ => {},
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use graphics::Context;
+use graphics::Transformed;
+
+use crate::fonts::Font;
+use crate::render::Backend;
+use crate::render::Render;
+use crate::widgets;
+use crate::widgets::WidgetState;
+
+
+/// text which doesn't respond to input
+pub struct Label {
+    pub id: &'static str,
+    pub text: String,
+    pub size: u32,
+    pub rect: [f64; 4],
+    pub state: WidgetState,
+}
+
+impl Label {
+    pub fn new(id: &'static str, text: &str, size: u32) -> Label {
+        Label { id, text: text.to_string(), size, rect: [0.0; 4], state: WidgetState::default() }
+    }
+}
+
+impl Render for Label {
+    fn render<G: Backend>(&self, c: &Context, g: &mut G) {
+        let [x, _, _, _] = self.rect;
+        let y = widgets::baseline(self.rect, self.size);
+        g.draw_text(self.state.color(), self.size, &self.text, Font::Regular, c.transform.trans(x, y));
+    }
+}
@@ -0,0 +1,172 @@
+use graphics::Context;
+use graphics::Rectangle;
+use graphics::rectangle;
+use graphics::Transformed;
+
+use crate::actions::Action;
+use crate::colors;
+use crate::events::EventBus;
+use crate::fonts::Font;
+use crate::hover::Hover;
+use crate::press::Press;
+use crate::release::Release;
+use crate::render::Backend;
+use crate::render::Render;
+use crate::scroll::Scroll;
+use crate::widgets;
+use crate::widgets::WidgetEvent;
+use crate::widgets::WidgetState;
+
+
+const SCROLLBAR_WIDTH: f64 = 4.0;
+const SELECTED_BACKGROUND: [f32; 4] = [0.67, 0.26, 0.22, 0.25];
+
+
+/// rows of text, as many as fit in the rect, scrolled with the mouse wheel or by moving the
+/// selection with Up and Down. Up on the first row or Down on the last moves focus on instead.
+/// reports Selected with the index of the row chosen, and Confirmed when Confirm is pressed on it
+pub struct List {
+    pub id: &'static str,
+    pub items: Vec<String>,
+    pub selected: Option<usize>,
+    /// index of the first row shown
+    pub scroll: usize,
+    /// height of each row, laid out along with the rect
+    pub row_height: f64,
+    /// the visible row under the cursor, counting from the top of the list
+    hovered_row: Option<usize>,
+    pub rect: [f64; 4],
+    pub state: WidgetState,
+    pub(super) events: Vec<WidgetEvent>,
+}
+
+impl List {
+    pub fn new(id: &'static str, items: Vec<String>) -> List {
+        List {
+            id,
+            items,
+            selected: None,
+            scroll: 0,
+            row_height: widgets::HEIGHT,
+            hovered_row: None,
+            rect: [0.0; 4],
+            state: WidgetState::default(),
+            events: Vec::new(),
+        }
+    }
+
+    /// the number of rows that fit
+    fn rows(&self) -> usize {
+        (self.rect[3] / self.row_height).floor().max(0.0) as usize
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.items.len().saturating_sub(self.rows())
+    }
+
+    fn row_rect(&self, row: usize) -> [f64; 4] {
+        let [x, y, w, _] = self.rect;
+        [x, y + self.row_height * row as f64, w, self.row_height]
+    }
+
+    /// index of the item under the cursor
+    fn hovered_item(&self) -> Option<usize> {
+        self.hovered_row.map(|row| row + self.scroll).filter(|i| *i < self.items.len())
+    }
+
+    /// whether Up or Down moves the selection, rather than going past the first or last row
+    pub fn moves_selection(&self, action: Action) -> bool {
+        let n = self.items.len();
+        match action {
+            Action::Up => n > 0 && self.selected != Some(0),
+            Action::Down => n > 0 && self.selected.is_none_or(|i| i + 1 < n),
+            _ => false,
+        }
+    }
+
+    /// select item i, scrolling it into view
+    fn select(&mut self, i: usize) {
+        if self.selected != Some(i) {
+            self.selected = Some(i);
+            self.events.push(WidgetEvent::Selected(self.id, i));
+        }
+        if i < self.scroll {
+            self.scroll = i;
+        } else if i >= self.scroll + self.rows() {
+            self.scroll = (i + 1).saturating_sub(self.rows());
+        }
+    }
+}
+
+impl Render for List {
+    fn render<G: Backend>(&self, c: &Context, g: &mut G) {
+        let color = self.state.color();
+        Rectangle::new_border(color, widgets::LINE / 2.0).draw(self.rect, &c.draw_state, c.transform, g);
+        let (size, padding) = (widgets::font_size(self.row_height), widgets::padding(self.row_height));
+        for (row, (i, item)) in self.items.iter().enumerate().skip(self.scroll).take(self.rows()).enumerate() {
+            let rect = self.row_rect(row);
+            if self.selected == Some(i) {
+                rectangle(SELECTED_BACKGROUND, rect, c.transform, g);
+            }
+            let item_color = if self.state.disabled {
+                widgets::DISABLED
+            } else if self.hovered_item() == Some(i) || self.selected == Some(i) {
+                colors::palette().mars
+            } else {
+                colors::palette().red
+            };
+            let y = widgets::baseline(rect, size);
+            g.draw_text(item_color, size, item, Font::Regular, c.transform.trans(rect[0] + padding, y));
+        }
+        // a bar on the right showing which part of the list is in view
+        if self.items.len() > self.rows() {
+            let [x, y, w, h] = self.rect;
+            let bar_height = h * self.rows() as f64 / self.items.len() as f64;
+            let bar_y = y + (h - bar_height) * self.scroll as f64 / self.max_scroll() as f64;
+            rectangle(color, [x + w - SCROLLBAR_WIDTH, bar_y, SCROLLBAR_WIDTH, bar_height], c.transform, g);
+        }
+    }
+}
+
+impl Hover for List {
+    fn hover(&mut self, _window_size: [f64; 2], cursor: [f64; 2], _bus: &mut EventBus) {
+        self.state.hovered = widgets::contains(self.rect, cursor);
+        self.hovered_row = (0..self.rows()).find(|row| widgets::contains(self.row_rect(*row), cursor));
+    }
+}
+
+impl Press for List {
+    fn press(&mut self, action: Action, _bus: &mut EventBus) {
+        let n = self.items.len();
+        match action {
+            Action::Select => self.state.press(),
+            Action::Up if self.state.active() && n > 0 => self.select(self.selected.map_or(n - 1, |i| i.saturating_sub(1))),
+            Action::Down if self.state.active() && n > 0 => self.select(self.selected.map_or(0, |i| (i + 1).min(n - 1))),
+            _ => {},
+        }
+    }
+}
+
+impl Release for List {
+    fn release(&mut self, action: Action, _bus: &mut EventBus) {
+        match action {
+            Action::Select if self.state.release() => if let Some(i) = self.hovered_item() {
+                self.select(i);
+            },
+            Action::Confirm if self.state.active() => if let Some(i) = self.selected {
+                self.events.push(WidgetEvent::Confirmed(self.id, i));
+            },
+            _ => {},
+        }
+    }
+}
+
+impl Scroll for List {
+    fn scroll(&mut self, delta: [f64; 2], _bus: &mut EventBus) {
+        if !self.state.hovered || self.state.disabled {
+            return
+        }
+        let scroll = self.scroll as f64 - delta[1].round();
+        self.scroll = scroll.max(0.0).min(self.max_scroll() as f64) as usize;
+    }
+}
@@ -0,0 +1,298 @@
+use graphics::Context;
+use graphics::types::Color;
+
+use crate::actions::Action;
+use crate::colors;
+use crate::events::EventBus;
+use crate::hover::Hover;
+use crate::press::Press;
+use crate::release::Release;
+use crate::render::Backend;
+use crate::render::Render;
+use crate::scroll::Scroll;
+use crate::text::Text;
+
+pub use self::button::Button;
+pub use self::dropdown::Dropdown;
+pub use self::label::Label;
+pub use self::list::List;
+pub use self::panel::Panel;
+pub use self::slider::Slider;
+pub use self::text_input::TextInput;
+pub use self::toggle::Toggle;
+
+mod button;
+mod dropdown;
+mod label;
+mod list;
+mod panel;
+mod slider;
+mod text_input;
+mod toggle;
+
+
+/// height of a single line widget at the reference size. text and padding are sized in proportion
+/// to the height a widget is laid out with, so they scale along with the layout
+pub const HEIGHT: f64 = 40.0;
+const FONT_SIZE: f64 = 24.0;
+const PADDING: f64 = 8.0;
+/// thickness of outlines, and of the underline beneath the focused widget
+pub const LINE: f64 = 2.0;
+pub const BACKGROUND: [f32; 4] = [0.05, 0.05, 0.05, 0.9];
+pub const DISABLED: [f32; 4] = [0.35, 0.35, 0.35, 1.0];
+
+
+/// what a widget reports to whoever owns it, named by the widget's id. collected with
+/// Widget::take_events after input has been handled
+#[derive(Clone, Debug, PartialEq)]
+pub enum WidgetEvent {
+    Clicked(&'static str),
+    Toggled(&'static str, bool),
+    /// a slider moved to a new value
+    Changed(&'static str, f64),
+    /// the index chosen in a dropdown or list
+    Selected(&'static str, usize),
+    /// Confirm pressed in a text input, with its text
+    Submitted(&'static str, String),
+    /// Confirm pressed on a list, with the index of the selected row
+    Confirmed(&'static str, usize),
+}
+
+
+/// interaction state shared by every widget
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WidgetState {
+    pub hovered: bool,
+    /// Select went down on the widget and hasn't come back up
+    pub pressed: bool,
+    /// Confirm and the other keyboard and controller actions go to this widget
+    pub focused: bool,
+    /// drawn greyed out and ignores input
+    pub disabled: bool,
+}
+
+impl WidgetState {
+    /// the color for the widget's text and outlines
+    pub fn color(&self) -> Color {
+        if self.disabled {
+            DISABLED
+        } else if self.hovered || self.focused {
+            colors::palette().mars
+        } else {
+            colors::palette().red
+        }
+    }
+
+    /// Select went down, starting a press if the cursor is on the widget
+    fn press(&mut self) {
+        self.pressed = self.hovered && !self.disabled;
+    }
+
+    /// Select came up, true if that finished a press on the widget
+    fn release(&mut self) -> bool {
+        let clicked = self.pressed && self.hovered;
+        self.pressed = false;
+        clicked
+    }
+
+    /// whether an action other than Select applies to the widget
+    fn active(&self) -> bool {
+        self.focused && !self.disabled
+    }
+}
+
+
+/// whether point is inside the rectangle [x, y, w, h]
+pub fn contains(rect: [f64; 4], point: [f64; 2]) -> bool {
+    let [x, y, w, h] = rect;
+    let [px, py] = point;
+    x <= px && px < x + w && y <= py && py < y + h
+}
+
+/// size of the text in a row of the given height
+pub fn font_size(height: f64) -> u32 {
+    ((FONT_SIZE * height / HEIGHT).round() as u32).max(1)
+}
+
+/// space between the edge of a row of the given height and its contents
+pub fn padding(height: f64) -> f64 {
+    PADDING * height / HEIGHT
+}
+
+/// y of the baseline for a line of text of the given size, centered vertically in rect
+pub fn baseline(rect: [f64; 4], size: u32) -> f64 {
+    rect[1] + (rect[3] + size as f64 * 0.7) / 2.0
+}
+
+
+/// any widget, so panels can hold a mix of them
+pub enum Widget {
+    Button(Button),
+    Dropdown(Dropdown),
+    Label(Label),
+    List(List),
+    Slider(Slider),
+    TextInput(TextInput),
+    Toggle(Toggle),
+}
+
+impl Widget {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Widget::Button(widget) => widget.id,
+            Widget::Dropdown(widget) => widget.id,
+            Widget::Label(widget) => widget.id,
+            Widget::List(widget) => widget.id,
+            Widget::Slider(widget) => widget.id,
+            Widget::TextInput(widget) => widget.id,
+            Widget::Toggle(widget) => widget.id,
+        }
+    }
+
+    pub fn state(&self) -> &WidgetState {
+        match self {
+            Widget::Button(widget) => &widget.state,
+            Widget::Dropdown(widget) => &widget.state,
+            Widget::Label(widget) => &widget.state,
+            Widget::List(widget) => &widget.state,
+            Widget::Slider(widget) => &widget.state,
+            Widget::TextInput(widget) => &widget.state,
+            Widget::Toggle(widget) => &widget.state,
+        }
+    }
+
+    pub fn state_mut(&mut self) -> &mut WidgetState {
+        match self {
+            Widget::Button(widget) => &mut widget.state,
+            Widget::Dropdown(widget) => &mut widget.state,
+            Widget::Label(widget) => &mut widget.state,
+            Widget::List(widget) => &mut widget.state,
+            Widget::Slider(widget) => &mut widget.state,
+            Widget::TextInput(widget) => &mut widget.state,
+            Widget::Toggle(widget) => &mut widget.state,
+        }
+    }
+
+    pub fn set_rect(&mut self, rect: [f64; 4]) {
+        match self {
+            Widget::Button(widget) => widget.rect = rect,
+            Widget::Dropdown(widget) => widget.rect = rect,
+            Widget::Label(widget) => widget.rect = rect,
+            Widget::List(widget) => widget.rect = rect,
+            Widget::Slider(widget) => widget.rect = rect,
+            Widget::TextInput(widget) => widget.rect = rect,
+            Widget::Toggle(widget) => widget.rect = rect,
+        }
+    }
+
+    /// whether the widget can take focus. labels only show things
+    pub fn focusable(&self) -> bool {
+        match self {
+            Widget::Label(_) => false,
+            _ => !self.state().disabled,
+        }
+    }
+
+    /// whether the widget uses action itself while focused, instead of it moving focus. only asked
+    /// about Up and Down
+    pub fn navigates(&self, action: Action) -> bool {
+        match self {
+            Widget::Dropdown(widget) => widget.open,
+            Widget::List(widget) => widget.moves_selection(action),
+            _ => false,
+        }
+    }
+
+    /// whether the widget takes all pointer input, e.g. an open dropdown covering the widgets below
+    pub fn captures(&self) -> bool {
+        match self {
+            Widget::Dropdown(widget) => widget.open,
+            _ => false,
+        }
+    }
+
+    /// move everything the widget has reported since last time into events
+    pub fn take_events(&mut self, events: &mut Vec<WidgetEvent>) {
+        match self {
+            Widget::Button(widget) => events.append(&mut widget.events),
+            Widget::Dropdown(widget) => events.append(&mut widget.events),
+            Widget::Label(_) => {},
+            Widget::List(widget) => events.append(&mut widget.events),
+            Widget::Slider(widget) => events.append(&mut widget.events),
+            Widget::TextInput(widget) => events.append(&mut widget.events),
+            Widget::Toggle(widget) => events.append(&mut widget.events),
+        }
+    }
+}
+
+impl Render for Widget {
+    fn render<G: Backend>(&self, c: &Context, g: &mut G) {
+        match self {
+            Widget::Button(widget) => widget.render(c, g),
+            Widget::Dropdown(widget) => widget.render(c, g),
+            Widget::Label(widget) => widget.render(c, g),
+            Widget::List(widget) => widget.render(c, g),
+            Widget::Slider(widget) => widget.render(c, g),
+            Widget::TextInput(widget) => widget.render(c, g),
+            Widget::Toggle(widget) => widget.render(c, g),
+        }
+    }
+}
+
+impl Hover for Widget {
+    fn hover(&mut self, window_size: [f64; 2], cursor: [f64; 2], bus: &mut EventBus) {
+        match self {
+            Widget::Button(widget) => widget.hover(window_size, cursor, bus),
+            Widget::Dropdown(widget) => widget.hover(window_size, cursor, bus),
+            Widget::Label(_) => {},
+            Widget::List(widget) => widget.hover(window_size, cursor, bus),
+            Widget::Slider(widget) => widget.hover(window_size, cursor, bus),
+            Widget::TextInput(widget) => widget.hover(window_size, cursor, bus),
+            Widget::Toggle(widget) => widget.hover(window_size, cursor, bus),
+        }
+    }
+}
+
+impl Press for Widget {
+    fn press(&mut self, action: Action, bus: &mut EventBus) {
+        match self {
+            Widget::Button(widget) => widget.press(action, bus),
+            Widget::Dropdown(widget) => widget.press(action, bus),
+            Widget::Label(_) => {},
+            Widget::List(widget) => widget.press(action, bus),
+            Widget::Slider(widget) => widget.press(action, bus),
+            Widget::TextInput(widget) => widget.press(action, bus),
+            Widget::Toggle(widget) => widget.press(action, bus),
+        }
+    }
+}
+
+impl Release for Widget {
+    fn release(&mut self, action: Action, bus: &mut EventBus) {
+        match self {
+            Widget::Button(widget) => widget.release(action, bus),
+            Widget::Dropdown(widget) => widget.release(action, bus),
+            Widget::Label(_) => {},
+            Widget::List(widget) => widget.release(action, bus),
+            Widget::Slider(widget) => widget.release(action, bus),
+            Widget::TextInput(widget) => widget.release(action, bus),
+            Widget::Toggle(widget) => widget.release(action, bus),
+        }
+    }
+}
+
+impl Text for Widget {
+    fn text(&mut self, text: &str, bus: &mut EventBus) {
+        if let Widget::TextInput(widget) = self {
+            widget.text(text, bus);
+        }
+    }
+}
+
+impl Scroll for Widget {
+    fn scroll(&mut self, delta: [f64; 2], bus: &mut EventBus) {
+        if let Widget::List(widget) = self {
+            widget.scroll(delta, bus);
+        }
+    }
+}
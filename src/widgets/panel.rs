@@ -0,0 +1,167 @@
+use graphics::Context;
+
+use crate::actions::Action;
+use crate::app::AppEvent;
+use crate::events::EventBus;
+use crate::hover::Hover;
+use crate::press::Press;
+use crate::release::Release;
+use crate::render::Backend;
+use crate::render::Render;
+use crate::scroll::Scroll;
+use crate::sounds::Sound;
+use crate::text::Text;
+use crate::widgets::Widget;
+use crate::widgets::WidgetEvent;
+
+
+/// holds other widgets, passing input on to them and moving focus between them. focus follows the
+/// cursor, and moves with Up, Down and Next
+pub struct Panel {
+    pub children: Vec<Widget>,
+    /// index of the focused child
+    focused: Option<usize>,
+}
+
+impl Panel {
+    pub fn new() -> Panel {
+        Panel { children: Vec::new(), focused: None }
+    }
+
+    pub fn child(mut self, child: Widget) -> Panel {
+        self.children.push(child);
+        self
+    }
+
+    /// the widget with this id
+    pub fn find(&self, id: &str) -> Option<&Widget> {
+        self.children.iter().find(|child| child.id() == id)
+    }
+
+    pub fn find_mut(&mut self, id: &str) -> Option<&mut Widget> {
+        self.children.iter_mut().find(|child| child.id() == id)
+    }
+
+    /// everything the children have reported since last time, in order
+    pub fn take_events(&mut self) -> Vec<WidgetEvent> {
+        let mut events = Vec::new();
+        for child in self.children.iter_mut() {
+            child.take_events(&mut events);
+        }
+        events
+    }
+
+    /// move focus to child i, with a tap if it moved
+    fn focus(&mut self, i: usize, bus: &mut EventBus) {
+        if self.focused != Some(i) {
+            bus.emit(AppEvent::PlaySound(Sound::TapMuted));
+        }
+        for (j, child) in self.children.iter_mut().enumerate() {
+            child.state_mut().focused = i == j;
+        }
+        self.focused = Some(i);
+    }
+
+    /// move focus by offset focusable children, wrapping around at either end
+    fn move_focus(&mut self, offset: isize, bus: &mut EventBus) {
+        let focusable: Vec<usize> = (0..self.children.len()).filter(|i| self.children[*i].focusable()).collect();
+        if focusable.is_empty() {
+            return
+        }
+        let n = focusable.len() as isize;
+        let next = match self.focused.and_then(|i| focusable.iter().position(|f| *f == i)) {
+            Some(position) => (position as isize + offset).rem_euclid(n),
+            None if offset > 0 => 0,
+            None => n - 1,
+        };
+        self.focus(focusable[next as usize], bus);
+    }
+
+    /// the child taking all pointer input, if any
+    pub fn capturing(&self) -> Option<usize> {
+        self.children.iter().position(Widget::captures)
+    }
+
+    /// id of the child under the cursor, if any. only a capturing child sees the cursor while it
+    /// captures, so the others are ignored
+    pub fn hovered(&self) -> Option<&'static str> {
+        match self.capturing() {
+            Some(i) => Some(&self.children[i]).filter(|child| child.state().hovered).map(Widget::id),
+            None => self.children.iter().find(|child| child.state().hovered).map(Widget::id),
+        }
+    }
+}
+
+impl Render for Panel {
+    fn render<G: Backend>(&self, c: &Context, g: &mut G) {
+        // a capturing child, e.g. an open dropdown, is drawn last to cover the others
+        let capturing = self.capturing();
+        for (i, child) in self.children.iter().enumerate() {
+            if Some(i) != capturing {
+                child.render(c, g);
+            }
+        }
+        if let Some(i) = capturing {
+            self.children[i].render(c, g);
+        }
+    }
+}
+
+impl Hover for Panel {
+    fn hover(&mut self, window_size: [f64; 2], cursor: [f64; 2], bus: &mut EventBus) {
+        if let Some(i) = self.capturing() {
+            self.children[i].hover(window_size, cursor, bus);
+            return
+        }
+        for child in self.children.iter_mut() {
+            child.hover(window_size, cursor, bus);
+        }
+        if let Some(i) = self.children.iter().position(|child| child.focusable() && child.state().hovered) {
+            self.focus(i, bus);
+        }
+    }
+}
+
+impl Press for Panel {
+    fn press(&mut self, action: Action, bus: &mut EventBus) {
+        let navigates = self.focused.is_some_and(|i| self.children[i].navigates(action));
+        match action {
+            Action::Up if !navigates => self.move_focus(-1, bus),
+            Action::Down if !navigates => self.move_focus(1, bus),
+            Action::Next => self.move_focus(1, bus),
+            _ => match self.capturing() {
+                Some(i) => self.children[i].press(action, bus),
+                None => for child in self.children.iter_mut() {
+                    child.press(action, bus);
+                },
+            },
+        }
+    }
+}
+
+impl Release for Panel {
+    fn release(&mut self, action: Action, bus: &mut EventBus) {
+        match self.capturing() {
+            Some(i) => self.children[i].release(action, bus),
+            None => for child in self.children.iter_mut() {
+                child.release(action, bus);
+            },
+        }
+    }
+}
+
+impl Text for Panel {
+    fn text(&mut self, text: &str, bus: &mut EventBus) {
+        for child in self.children.iter_mut() {
+            child.text(text, bus);
+        }
+    }
+}
+
+impl Scroll for Panel {
+    fn scroll(&mut self, delta: [f64; 2], bus: &mut EventBus) {
+        for child in self.children.iter_mut() {
+            child.scroll(delta, bus);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use graphics::Context;
+use graphics::rectangle;
+use graphics::Transformed;
+
+use crate::actions::Action;
+use crate::events::EventBus;
+use crate::fonts::Font;
+use crate::hover::Hover;
+use crate::press::Press;
+use crate::release::Release;
+use crate::render::Backend;
+use crate::render::Render;
+use crate::widgets;
+use crate::widgets::WidgetEvent;
+use crate::widgets::WidgetState;
+
+
+/// fraction of the slider's width taken by its text, the track fills the rest
+const TEXT_WIDTH: f64 = 0.4;
+/// width of the handle at the reference height
+const HANDLE_WIDTH: f64 = 10.0;
+
+
+/// text and a value between min and max, dragged with the pointer or stepped with Left and Right.
+/// reports Changed with the new value
+pub struct Slider {
+    pub id: &'static str,
+    pub text: String,
+    pub value: f64,
+    pub min: f64,
+    pub max: f64,
+    /// values snap to multiples of this above min
+    pub step: f64,
+    /// how the value is shown after the text
+    pub format: fn(f64) -> String,
+    pub rect: [f64; 4],
+    pub state: WidgetState,
+    pub(super) events: Vec<WidgetEvent>,
+    /// where the cursor last was, so pressing Select can jump the handle there
+    cursor: [f64; 2],
+}
+
+impl Slider {
+    pub fn new(id: &'static str, text: &str, value: f64, min: f64, max: f64, step: f64) -> Slider {
+        Slider {
+            id,
+            text: text.to_string(),
+            value,
+            min,
+            max,
+            step,
+            format: |value| format!("{}", value),
+            rect: [0.0; 4],
+            state: WidgetState::default(),
+            events: Vec::new(),
+            cursor: [0.0, 0.0],
+        }
+    }
+
+    pub fn format(mut self, format: fn(f64) -> String) -> Slider {
+        self.format = format;
+        self
+    }
+
+    fn handle_width(&self) -> f64 {
+        HANDLE_WIDTH * self.rect[3] / widgets::HEIGHT
+    }
+
+    /// [x, y, w, h] of the part of the slider the handle moves along
+    fn track(&self) -> [f64; 4] {
+        let [x, y, w, h] = self.rect;
+        let text_width = w * TEXT_WIDTH;
+        [x + text_width, y, w - text_width - self.handle_width(), h]
+    }
+
+    /// clamp and snap value, reporting it if it changed
+    fn set(&mut self, value: f64) {
+        let steps = ((value - self.min) / self.step).round();
+        let value = (self.min + steps * self.step).max(self.min).min(self.max);
+        if value != self.value {
+            self.value = value;
+            self.events.push(WidgetEvent::Changed(self.id, value));
+        }
+    }
+
+    /// move the handle to the cursor
+    fn drag(&mut self, cursor: [f64; 2]) {
+        let [x, _, w, _] = self.track();
+        let t = if w > 0.0 { (cursor[0] - x) / w } else { 0.0 };
+        self.set(self.min + t.clamp(0.0, 1.0) * (self.max - self.min));
+    }
+}
+
+impl Render for Slider {
+    fn render<G: Backend>(&self, c: &Context, g: &mut G) {
+        let color = self.state.color();
+        let text = format!("{}: {}", self.text, (self.format)(self.value));
+        let [x, y, w, h] = self.track();
+        let (size, padding, handle_width) = (widgets::font_size(h), widgets::padding(h), self.handle_width());
+        let text_y = widgets::baseline(self.rect, size);
+        g.draw_text(color, size, &text, Font::Regular, c.transform.trans(self.rect[0] + padding, text_y));
+
+        rectangle(color, [x, y + (h - widgets::LINE) / 2.0, w + handle_width, widgets::LINE], c.transform, g);
+        let range = self.max - self.min;
+        let t = if range > 0.0 { (self.value - self.min) / range } else { 0.0 };
+        rectangle(color, [x + t * w, y + padding, handle_width, h - padding * 2.0], c.transform, g);
+    }
+}
+
+impl Hover for Slider {
+    fn hover(&mut self, _window_size: [f64; 2], cursor: [f64; 2], _bus: &mut EventBus) {
+        self.state.hovered = widgets::contains(self.rect, cursor);
+        self.cursor = cursor;
+        // keeps following the cursor off the slider until Select comes up
+        if self.state.pressed {
+            self.drag(cursor);
+        }
+    }
+}
+
+impl Press for Slider {
+    fn press(&mut self, action: Action, _bus: &mut EventBus) {
+        match action {
+            Action::Select => {
+                self.state.press();
+                if self.state.pressed {
+                    self.drag(self.cursor);
+                }
+            },
+            Action::Left if self.state.active() => self.set(self.value - self.step),
+            Action::Right if self.state.active() => self.set(self.value + self.step),
+            _ => {},
+        }
+    }
+}
+
+impl Release for Slider {
+    fn release(&mut self, action: Action, _bus: &mut EventBus) {
+        if let Action::Select = action {
+            self.state.release();
+        }
+    }
+}
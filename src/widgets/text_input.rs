@@ -0,0 +1,105 @@
+use graphics::Context;
+use graphics::Rectangle;
+use graphics::Transformed;
+
+use crate::actions::Action;
+use crate::events::EventBus;
+use crate::fonts::Font;
+use crate::hover::Hover;
+use crate::press::Press;
+use crate::release::Release;
+use crate::render::Backend;
+use crate::render::Render;
+use crate::text::Text;
+use crate::widgets;
+use crate::widgets::WidgetEvent;
+use crate::widgets::WidgetState;
+
+
+/// a box to type a line of text into while focused. Erase deletes the last character, and Confirm
+/// reports Submitted with the text
+pub struct TextInput {
+    pub id: &'static str,
+    pub text: String,
+    /// shown greyed out while the input is empty
+    pub placeholder: String,
+    /// characters accepted, anything typed past this is dropped
+    pub max_len: usize,
+    pub rect: [f64; 4],
+    pub state: WidgetState,
+    pub(super) events: Vec<WidgetEvent>,
+}
+
+impl TextInput {
+    pub fn new(id: &'static str, placeholder: &str, max_len: usize) -> TextInput {
+        TextInput {
+            id,
+            text: String::new(),
+            placeholder: placeholder.to_string(),
+            max_len,
+            rect: [0.0; 4],
+            state: WidgetState::default(),
+            events: Vec::new(),
+        }
+    }
+}
+
+impl Render for TextInput {
+    fn render<G: Backend>(&self, c: &Context, g: &mut G) {
+        let color = self.state.color();
+        Rectangle::new_border(color, widgets::LINE / 2.0).draw(self.rect, &c.draw_state, c.transform, g);
+        let (size, padding) = (widgets::font_size(self.rect[3]), widgets::padding(self.rect[3]));
+        let transform = c.transform.trans(self.rect[0] + padding, widgets::baseline(self.rect, size));
+        if self.text.is_empty() && !self.state.active() {
+            g.draw_text(widgets::DISABLED, size, &self.placeholder, Font::Regular, transform);
+        } else if self.state.active() {
+            g.draw_text(color, size, &format!("{}_", self.text), Font::Regular, transform);
+        } else {
+            g.draw_text(color, size, &self.text, Font::Regular, transform);
+        }
+    }
+}
+
+impl Hover for TextInput {
+    fn hover(&mut self, _window_size: [f64; 2], cursor: [f64; 2], _bus: &mut EventBus) {
+        self.state.hovered = widgets::contains(self.rect, cursor);
+    }
+}
+
+impl Press for TextInput {
+    fn press(&mut self, action: Action, _bus: &mut EventBus) {
+        match action {
+            Action::Select => self.state.press(),
+            Action::Erase if self.state.active() => {
+                self.text.pop();
+            },
+            _ => {},
+        }
+    }
+}
+
+impl Release for TextInput {
+    fn release(&mut self, action: Action, _bus: &mut EventBus) {
+        match action {
+            Action::Select => {
+                self.state.release();
+            },
+            Action::Confirm if self.state.active() => self.events.push(WidgetEvent::Submitted(self.id, self.text.clone())),
+            _ => {},
+        }
+    }
+}
+
+impl Text for TextInput {
+    fn text(&mut self, text: &str, _bus: &mut EventBus) {
+        if !self.state.active() {
+            return
+        }
+        for ch in text.chars().filter(|ch| !ch.is_control()) {
+            if self.text.chars().count() == self.max_len {
+                break
+            }
+            self.text.push(ch);
+        }
+    }
+}
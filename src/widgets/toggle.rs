@@ -0,0 +1,83 @@
+use graphics::Context;
+use graphics::Rectangle;
+use graphics::rectangle;
+use graphics::Transformed;
+
+use crate::actions::Action;
+use crate::events::EventBus;
+use crate::fonts::Font;
+use crate::hover::Hover;
+use crate::press::Press;
+use crate::release::Release;
+use crate::render::Backend;
+use crate::render::Render;
+use crate::widgets;
+use crate::widgets::WidgetEvent;
+use crate::widgets::WidgetState;
+
+
+/// a checkbox followed by text, reports Toggled with the new value when flipped
+pub struct Toggle {
+    pub id: &'static str,
+    pub text: String,
+    pub on: bool,
+    pub rect: [f64; 4],
+    pub state: WidgetState,
+    pub(super) events: Vec<WidgetEvent>,
+}
+
+impl Toggle {
+    pub fn new(id: &'static str, text: &str, on: bool) -> Toggle {
+        Toggle { id, text: text.to_string(), on, rect: [0.0; 4], state: WidgetState::default(), events: Vec::new() }
+    }
+
+    fn flip(&mut self) {
+        self.on = !self.on;
+        self.events.push(WidgetEvent::Toggled(self.id, self.on));
+    }
+}
+
+impl Render for Toggle {
+    fn render<G: Backend>(&self, c: &Context, g: &mut G) {
+        let [x, y, _, h] = self.rect;
+        let color = self.state.color();
+        let (size, padding) = (widgets::font_size(h), widgets::padding(h));
+        let side = (h - padding * 2.0).max(0.0);
+        let check = [x + padding, y + padding, side, side];
+        Rectangle::new_border(color, widgets::LINE / 2.0).draw(check, &c.draw_state, c.transform, g);
+        if self.on {
+            let inset = widgets::LINE * 2.0;
+            rectangle(color, [check[0] + inset, check[1] + inset, side - inset * 2.0, side - inset * 2.0], c.transform, g);
+        }
+        let text_x = check[0] + side + padding * 2.0;
+        let text_y = widgets::baseline(self.rect, size);
+        g.draw_text(color, size, &self.text, Font::Regular, c.transform.trans(text_x, text_y));
+    }
+}
+
+impl Hover for Toggle {
+    fn hover(&mut self, _window_size: [f64; 2], cursor: [f64; 2], _bus: &mut EventBus) {
+        self.state.hovered = widgets::contains(self.rect, cursor);
+    }
+}
+
+impl Press for Toggle {
+    fn press(&mut self, action: Action, _bus: &mut EventBus) {
+        if let Action::Select = action {
+            self.state.press();
+        }
+    }
+}
+
+impl Release for Toggle {
+    fn release(&mut self, action: Action, _bus: &mut EventBus) {
+        let activated = match action {
+            Action::Select => self.state.release(),
+            Action::Confirm => self.state.active(),
+            _ => false,
+        };
+        if activated {
+            self.flip();
+        }
+    }
+}